@@ -1,11 +1,12 @@
 use std::error::Error;
 
-use futures::future::{BoxFuture, FutureExt};
 use isocountry::CountryCode;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     artist::SimpleArtist,
+    batch::{self, Endpoint},
+    id::{self, AlbumId},
     object::{Image, PagingObject},
     track::SimpleTrack,
     RequestClient,
@@ -41,12 +42,15 @@ pub struct SimpleAlbum {
     pub id: String,
     pub images: Vec<Image>,
     pub name: String,
+    pub release_date: Option<String>,
+    pub release_date_precision: Option<String>,
+    pub total_tracks: Option<u32>,
     #[serde(rename = "type")]
     pub object_type: String,
     pub uri: String,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct AlbumClient {
     client: RequestClient,
 }
@@ -58,11 +62,16 @@ impl AlbumClient {
         }
     }
 
-    pub async fn get_album(&mut self, request: GetAlbumRequest) -> Result<Album, Box<dyn Error>> {
+    pub(crate) fn with_client(client: RequestClient) -> AlbumClient {
+        AlbumClient { client }
+    }
+
+    pub async fn get_album(&self, request: GetAlbumRequest) -> Result<Album, Box<dyn Error>> {
         let url = format!("https://api.spotify.com/v1/albums/{}", request.id);
         let builder = reqwest::Client::new().get(&url);
         let response = self
             .client
+            .clone()
             .set_market(request.market)
             .send(builder)
             .await?
@@ -71,56 +80,52 @@ impl AlbumClient {
         Ok(response.json().await?)
     }
 
-    pub fn get_albums(
-        &mut self,
-        mut request: GetAlbumListRequest,
-    ) -> BoxFuture<'_, Result<GetAlbumListResponse, Box<dyn Error>>> {
-        async move {
-            let mut albums_response = GetAlbumListResponse::default();
-
-            if request.ids.len() > 20 {
-                let drained: Vec<String> = request.ids.drain(..20).collect();
-                let drained_request = GetAlbumListRequest {
-                    ids: drained,
-                    market: request.market,
-                };
-                albums_response
-                    .albums
-                    .append(&mut self.get_albums(drained_request).await?.albums);
-                albums_response
-                    .albums
-                    .append(&mut self.get_albums(request.clone()).await?.albums);
-
-                return Ok(albums_response);
-            }
-
-            let builder = reqwest::Client::new()
-                .get("https://api.spotify.com/v1/albums")
-                .query(&[("ids", request.ids.join(","))]);
-
-            let response = self
-                .client
-                .set_market(request.market)
-                .send(builder)
-                .await?
-                .unwrap();
-
-            let mut values: GetAlbumListResponse = response.json().await?;
-            albums_response.albums.append(&mut values.albums);
-
-            Ok(albums_response)
-        }
-        .boxed()
+    pub async fn get_albums(
+        &self,
+        request: GetAlbumListRequest,
+    ) -> Result<GetAlbumListResponse, Box<dyn Error>> {
+        let market = request.market;
+        let ids = id::into_strings(request.ids);
+        let albums = batch::run(Endpoint::Albums, ids, |_, ids| {
+            self.request_albums(ids, market)
+        })
+        .await
+        .into_items()?;
+
+        Ok(GetAlbumListResponse { albums })
+    }
+
+    async fn request_albums(
+        &self,
+        ids: Vec<String>,
+        market: Option<CountryCode>,
+    ) -> Result<Vec<Album>, Box<dyn Error>> {
+        let builder = reqwest::Client::new()
+            .get("https://api.spotify.com/v1/albums")
+            .query(&[("ids", ids.join(","))]);
+
+        let response = self
+            .client
+            .clone()
+            .set_market(market)
+            .send(builder)
+            .await?
+            .unwrap();
+
+        let values: GetAlbumListResponse = response.json().await?;
+
+        Ok(values.albums)
     }
 
     pub async fn get_tracks(
-        &mut self,
+        &self,
         request: GetTrackListRequest,
     ) -> Result<PagingObject<SimpleTrack>, Box<dyn Error>> {
         let url = format!("https://api.spotify.com/v1/albums/{}/tracks", request.id);
         let builder = reqwest::Client::new().get(&url);
         let response = self
             .client
+            .clone()
             .set_offset(request.offset)
             .set_limit(request.limit)
             .set_market(request.market)
@@ -132,15 +137,15 @@ impl AlbumClient {
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct GetAlbumRequest {
-    pub id: String,
+    pub id: AlbumId,
     pub market: Option<CountryCode>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct GetAlbumListRequest {
-    pub ids: Vec<String>,
+    pub ids: Vec<AlbumId>,
     pub market: Option<CountryCode>,
 }
 
@@ -149,9 +154,9 @@ pub struct GetAlbumListResponse {
     pub albums: Vec<Album>,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct GetTrackListRequest {
-    pub id: String,
+    pub id: AlbumId,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
     pub market: Option<CountryCode>,
@@ -1,11 +1,13 @@
-use std::{error::Error, fmt};
+use std::{collections::HashMap, error::Error, fmt};
 
-use futures::future::{BoxFuture, FutureExt};
 use isocountry::CountryCode;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    album::SimpleAlbum,
+    album::{Album, AlbumClient, GetAlbumListRequest, SimpleAlbum},
+    batch::{self, Endpoint},
+    graph::{self, ArtistGraph, ExploreRelatedRequest},
+    id::{self, ArtistId},
     object::{Follower, Image, PagingObject},
     track::Track,
     RequestClient,
@@ -35,7 +37,7 @@ pub struct SimpleArtist {
     pub uri: String,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct ArtistClient {
     client: RequestClient,
 }
@@ -47,10 +49,7 @@ impl ArtistClient {
         }
     }
 
-    pub async fn get_artist(
-        &mut self,
-        request: GetArtistRequest,
-    ) -> Result<Artist, Box<dyn Error>> {
+    pub async fn get_artist(&self, request: GetArtistRequest) -> Result<Artist, Box<dyn Error>> {
         let url = format!("https://api.spotify.com/v1/artists/{}", request.id);
         let builder = reqwest::Client::new().get(&url);
         let response = self.client.send(builder).await?.unwrap();
@@ -58,42 +57,31 @@ impl ArtistClient {
         Ok(response.json().await?)
     }
 
-    pub fn get_artists(
-        &mut self,
-        mut request: GetArtistListRequest,
-    ) -> BoxFuture<'_, Result<GetArtistListResponse, Box<dyn Error>>> {
-        async move {
-            let mut artist_response = GetArtistListResponse::default();
-
-            if request.ids.len() > 50 {
-                let drained: Vec<String> = request.ids.drain(..20).collect();
-                let drained_request = GetArtistListRequest { ids: drained };
-                artist_response
-                    .artists
-                    .append(&mut self.get_artists(drained_request).await?.artists);
-                artist_response
-                    .artists
-                    .append(&mut self.get_artists(request.clone()).await?.artists);
-
-                return Ok(artist_response);
-            }
+    pub async fn get_artists(
+        &self,
+        request: GetArtistListRequest,
+    ) -> Result<GetArtistListResponse, Box<dyn Error>> {
+        let ids = id::into_strings(request.ids);
+        let artists = batch::run(Endpoint::Artists, ids, |_, ids| self.request_artists(ids))
+            .await
+            .into_items()?;
 
-            let builder = reqwest::Client::new()
-                .get("https://api.spotify.com/v1/artists")
-                .query(&[("ids", request.ids.join(","))]);
+        Ok(GetArtistListResponse { artists })
+    }
 
-            let response = self.client.send(builder).await?.unwrap();
+    async fn request_artists(&self, ids: Vec<String>) -> Result<Vec<Artist>, Box<dyn Error>> {
+        let builder = reqwest::Client::new()
+            .get("https://api.spotify.com/v1/artists")
+            .query(&[("ids", ids.join(","))]);
 
-            let mut values: GetArtistListResponse = response.json().await?;
-            artist_response.artists.append(&mut values.artists);
+        let response = self.client.send(builder).await?.unwrap();
+        let values: GetArtistListResponse = response.json().await?;
 
-            Ok(artist_response)
-        }
-        .boxed()
+        Ok(values.artists)
     }
 
     pub async fn get_albums(
-        &mut self,
+        &self,
         request: GetArtistAlbumRequest,
     ) -> Result<PagingObject<SimpleAlbum>, Box<dyn Error>> {
         let url = format!("https://api.spotify.com/v1/artists/{}/albums", request.id);
@@ -114,6 +102,7 @@ impl ArtistClient {
 
         let response = self
             .client
+            .clone()
             .set_offset(request.offset)
             .set_limit(request.limit)
             .set_country(request.country)
@@ -124,8 +113,69 @@ impl ArtistClient {
         Ok(response.json().await?)
     }
 
+    pub async fn get_discography(
+        &self,
+        request: GetDiscographyRequest,
+    ) -> Result<Discography, Box<dyn Error>> {
+        let mut albums = Vec::new();
+        let mut offset = 0;
+
+        loop {
+            let mut page = self
+                .get_albums(GetArtistAlbumRequest {
+                    id: request.id.clone(),
+                    include_groups: request.include_groups.clone(),
+                    country: request.country,
+                    limit: Some(50),
+                    offset: Some(offset),
+                })
+                .await?;
+            let fetched = page.items.len();
+            offset += fetched as u32;
+            albums.append(&mut page.items);
+
+            if page.next.is_none() || fetched == 0 {
+                break;
+            }
+        }
+
+        let mut discography = Discography::collapse(albums);
+
+        if request.hydrate {
+            let ids = discography
+                .releases
+                .iter()
+                .map(|release| release.album.id.parse())
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut albums: HashMap<String, Album> = AlbumClient::with_client(self.client.clone())
+                .get_albums(GetAlbumListRequest {
+                    ids,
+                    market: request.country,
+                })
+                .await?
+                .albums
+                .into_iter()
+                .map(|album| (album.id.clone(), album))
+                .collect();
+
+            for release in &mut discography.releases {
+                release.full = albums.remove(&release.album.id);
+            }
+        }
+
+        Ok(discography)
+    }
+
+    pub async fn explore_related(
+        &self,
+        request: ExploreRelatedRequest,
+    ) -> Result<ArtistGraph, Box<dyn Error>> {
+        graph::explore(self, request).await
+    }
+
     pub async fn get_top_tracks(
-        &mut self,
+        &self,
         request: GetArtistTopTrackRequest,
     ) -> Result<GetArtistTopTrackResponse, Box<dyn Error>> {
         let url = format!(
@@ -147,7 +197,7 @@ impl ArtistClient {
     }
 
     pub async fn get_related_artists(
-        &mut self,
+        &self,
         request: GetRelatedArtistRequest,
     ) -> Result<GetRelatedArtistResponse, Box<dyn Error>> {
         let url = format!(
@@ -162,14 +212,14 @@ impl ArtistClient {
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct GetArtistRequest {
-    pub id: String,
+    pub id: ArtistId,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct GetArtistListRequest {
-    pub ids: Vec<String>,
+    pub ids: Vec<ArtistId>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -177,18 +227,89 @@ pub struct GetArtistListResponse {
     pub artists: Vec<Artist>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct GetArtistAlbumRequest {
-    pub id: String,
+    pub id: ArtistId,
     pub include_groups: Option<Vec<IncludeGroup>>,
     pub country: Option<CountryCode>,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
 }
 
+#[derive(Clone, Debug)]
+pub struct GetDiscographyRequest {
+    pub id: ArtistId,
+    pub include_groups: Option<Vec<IncludeGroup>>,
+    pub country: Option<CountryCode>,
+    pub hydrate: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Discography {
+    pub releases: Vec<Release>,
+}
+
+impl Discography {
+    pub fn collapse(albums: Vec<SimpleAlbum>) -> Self {
+        let mut releases: Vec<Release> = Vec::new();
+        let mut index: HashMap<(String, Option<String>, Option<u32>), usize> = HashMap::new();
+
+        for album in albums {
+            let key = (
+                normalize_album_name(&album.name),
+                album.release_date.clone(),
+                album.total_tracks,
+            );
+
+            match index.get(&key) {
+                Some(&i) => {
+                    let release = &mut releases[i];
+                    if market_count(&album) > market_count(&release.album) {
+                        let previous = std::mem::replace(&mut release.album, album);
+                        release.duplicates.push(previous);
+                    } else {
+                        release.duplicates.push(album);
+                    }
+                }
+                None => {
+                    index.insert(key, releases.len());
+                    releases.push(Release {
+                        album,
+                        duplicates: Vec::new(),
+                        full: None,
+                    });
+                }
+            }
+        }
+
+        Discography { releases }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
+pub struct Release {
+    pub album: SimpleAlbum,
+    pub duplicates: Vec<SimpleAlbum>,
+    pub full: Option<Album>,
+}
+
+pub fn normalize_album_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn market_count(album: &SimpleAlbum) -> usize {
+    album.available_markets.as_ref().map_or(0, Vec::len)
+}
+
+#[derive(Clone, Debug)]
 pub struct GetArtistTopTrackRequest {
-    pub id: String,
+    pub id: ArtistId,
     pub country: Option<CountryCode>,
 }
 
@@ -197,9 +318,9 @@ pub struct GetArtistTopTrackResponse {
     pub tracks: Vec<Track>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct GetRelatedArtistRequest {
-    pub id: String,
+    pub id: ArtistId,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
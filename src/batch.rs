@@ -0,0 +1,154 @@
+use std::{error::Error, fmt, future::Future, ops::Range};
+
+use futures::stream::{self, StreamExt};
+
+pub const CONCURRENCY: usize = 4;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Endpoint {
+    Albums,
+    Artists,
+    AudioFeatures,
+    Follow,
+    Library,
+    PlaylistFollowers,
+    PlaylistItems,
+    Tracks,
+}
+
+impl Endpoint {
+    pub fn limit(self) -> usize {
+        match self {
+            Endpoint::PlaylistFollowers => 5,
+            Endpoint::Albums => 20,
+            Endpoint::Artists | Endpoint::Follow | Endpoint::Library | Endpoint::Tracks => 50,
+            Endpoint::AudioFeatures | Endpoint::PlaylistItems => 100,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ChunkError {
+    pub index: usize,
+    pub range: Range<usize>,
+    pub error: Box<dyn Error>,
+}
+
+impl fmt::Display for ChunkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "chunk {} (items {}..{}): {}",
+            self.index, self.range.start, self.range.end, self.error
+        )
+    }
+}
+
+impl Error for ChunkError {}
+
+#[derive(Debug)]
+pub struct BatchError {
+    pub chunks: usize,
+    pub failures: Vec<ChunkError>,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} chunks failed",
+            self.failures.len(),
+            self.chunks
+        )?;
+        for failure in &self.failures {
+            write!(f, "; {}", failure)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for BatchError {}
+
+#[derive(Debug)]
+pub struct BatchResult<O> {
+    pub chunks: Vec<Result<O, ChunkError>>,
+}
+
+impl<O> BatchResult<O> {
+    pub fn is_ok(&self) -> bool {
+        self.chunks.iter().all(Result::is_ok)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &ChunkError> {
+        self.chunks.iter().filter_map(|chunk| chunk.as_ref().err())
+    }
+
+    pub fn into_result(self) -> Result<Vec<O>, BatchError> {
+        let chunks = self.chunks.len();
+        let mut outputs = Vec::new();
+        let mut failures = Vec::new();
+
+        for chunk in self.chunks {
+            match chunk {
+                Ok(output) => outputs.push(output),
+                Err(failure) => failures.push(failure),
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(outputs)
+        } else {
+            Err(BatchError { chunks, failures })
+        }
+    }
+}
+
+impl<T> BatchResult<Vec<T>> {
+    pub fn into_items(self) -> Result<Vec<T>, BatchError> {
+        Ok(self.into_result()?.into_iter().flatten().collect())
+    }
+}
+
+pub async fn run<I, O, F, Fut>(endpoint: Endpoint, items: Vec<I>, f: F) -> BatchResult<O>
+where
+    I: Clone,
+    F: Fn(usize, Vec<I>) -> Fut,
+    Fut: Future<Output = Result<O, Box<dyn Error>>>,
+{
+    run_with_concurrency(endpoint, CONCURRENCY, items, f).await
+}
+
+pub async fn run_with_concurrency<I, O, F, Fut>(
+    endpoint: Endpoint,
+    concurrency: usize,
+    items: Vec<I>,
+    f: F,
+) -> BatchResult<O>
+where
+    I: Clone,
+    F: Fn(usize, Vec<I>) -> Fut,
+    Fut: Future<Output = Result<O, Box<dyn Error>>>,
+{
+    let limit = endpoint.limit();
+    let requests = items.chunks(limit).enumerate().map(|(index, chunk)| {
+        let start = index * limit;
+        let range = start..start + chunk.len();
+        let future = f(start, chunk.to_vec());
+
+        async move {
+            future.await.map_err(|error| ChunkError {
+                index,
+                range,
+                error,
+            })
+        }
+    });
+
+    let chunks = stream::iter(requests)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await;
+
+    BatchResult { chunks }
+}
@@ -1,4 +1,8 @@
-use std::{error::Error, fmt};
+use std::{
+    error::Error,
+    fmt,
+    sync::{Arc, RwLock},
+};
 
 use chrono::{DateTime, Utc};
 use isocountry::CountryCode;
@@ -6,9 +10,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     album::SimpleAlbum,
+    error::ValidationError,
+    id::{ArtistId, Id, TrackId},
     object::{Image, PagingObject},
     playlist::SimplePlaylist,
-    track::SimpleTrack,
+    track::Track,
     RequestClient,
 };
 
@@ -23,17 +29,38 @@ pub struct Category {
 #[derive(Clone, Debug, Default)]
 pub struct BrowseClient {
     client: RequestClient,
+    genre_seeds: Arc<RwLock<Option<GenreSeeds>>>,
 }
 
 impl BrowseClient {
     pub fn new(access_token: &str, refresh_token: &str) -> Self {
         BrowseClient {
             client: RequestClient::new(access_token, refresh_token),
+            ..Default::default()
         }
     }
 
+    pub async fn get_available_genre_seeds(&self) -> Result<GenreSeeds, Box<dyn Error>> {
+        let builder = reqwest::Client::new()
+            .get("https://api.spotify.com/v1/recommendations/available-genre-seeds");
+        let response = self.client.send(builder).await?.unwrap();
+        let genre_seeds: GenreSeeds = response.json().await?;
+
+        *self.genre_seeds.write().unwrap() = Some(genre_seeds.clone());
+
+        Ok(genre_seeds)
+    }
+
+    pub async fn genre_seeds(&self) -> Result<GenreSeeds, Box<dyn Error>> {
+        if let Some(genre_seeds) = self.genre_seeds.read().unwrap().clone() {
+            return Ok(genre_seeds);
+        }
+
+        self.get_available_genre_seeds().await
+    }
+
     pub async fn get_category(
-        &mut self,
+        &self,
         request: GetCategoryRequest,
     ) -> Result<Category, Box<dyn Error>> {
         let url = format!(
@@ -56,7 +83,7 @@ impl BrowseClient {
     }
 
     pub async fn get_categories(
-        &mut self,
+        &self,
         request: GetCategoriesRequest,
     ) -> Result<GetCategoriesResponse, Box<dyn Error>> {
         let mut query = Vec::new();
@@ -73,6 +100,7 @@ impl BrowseClient {
 
         let response = self
             .client
+            .clone()
             .set_offset(request.offset)
             .set_limit(request.limit)
             .send(builder)
@@ -83,7 +111,7 @@ impl BrowseClient {
     }
 
     pub async fn get_category_playlists(
-        &mut self,
+        &self,
         request: GetCategoryPlaylistRequest,
     ) -> Result<GetCategoryPlaylistResponse, Box<dyn Error>> {
         let url = format!(
@@ -94,6 +122,7 @@ impl BrowseClient {
         let builder = reqwest::Client::new().get(&url);
         let response = self
             .client
+            .clone()
             .set_offset(request.offset)
             .set_limit(request.limit)
             .set_country(request.country)
@@ -105,7 +134,7 @@ impl BrowseClient {
     }
 
     pub async fn get_featured_playlists(
-        &mut self,
+        &self,
         request: GetFeaturedPlaylistRequest,
     ) -> Result<GetFeaturedPlaylistResponse, Box<dyn Error>> {
         let mut query = Vec::new();
@@ -128,6 +157,7 @@ impl BrowseClient {
 
         let response = self
             .client
+            .clone()
             .set_offset(request.offset)
             .set_limit(request.limit)
             .set_country(request.country)
@@ -139,12 +169,13 @@ impl BrowseClient {
     }
 
     pub async fn get_new_releases(
-        &mut self,
+        &self,
         request: GetNewReleaseRequest,
     ) -> Result<GetNewReleaseResponse, Box<dyn Error>> {
         let builder = reqwest::Client::new().get("https://api.spotify.com/v1/browse/new-releases");
         let response = self
             .client
+            .clone()
             .set_offset(request.offset)
             .set_limit(request.limit)
             .set_country(request.country)
@@ -156,15 +187,18 @@ impl BrowseClient {
     }
 
     pub async fn get_recommendations(
-        &mut self,
+        &self,
         request: GetRecommendationsRequest,
-    ) -> Result<GetRecommendationsResponse, Box<dyn Error>> {
+    ) -> Result<Recommendations, Box<dyn Error>> {
+        request.validate()?;
+
         let builder = reqwest::Client::new()
             .get("https://api.spotify.com/v1/recommendations")
             .query(&request.get_query());
 
         let resposne = self
             .client
+            .clone()
             .set_limit(request.limit)
             .set_market(request.market)
             .send(builder)
@@ -178,6 +212,9 @@ impl BrowseClient {
 pub mod recommendation {
     use std::collections::HashMap;
 
+    use super::{GenreSeeds, GetRecommendationsRequest};
+    use crate::error::ValidationError;
+
     #[derive(Clone, Eq, PartialEq, Debug, Default)]
     pub struct RecommendationFilter {
         pub max_attribute: HashMap<String, String>,
@@ -196,52 +233,75 @@ pub mod recommendation {
             }
         }
 
-        pub fn set_max_attribute(&mut self, attribute: TrackAttribute) -> &mut Self {
+        pub fn set_max_attribute(
+            &mut self,
+            attribute: TrackAttribute,
+        ) -> Result<&mut Self, ValidationError> {
+            attribute.validate()?;
+
             let mut key = "max_".to_string();
             key.push_str(&attribute.get_name());
             self.max_attribute.insert(key, attribute.get_value());
 
-            self
+            Ok(self)
         }
 
-        pub fn set_min_attribute(&mut self, attribute: TrackAttribute) -> &mut Self {
+        pub fn set_min_attribute(
+            &mut self,
+            attribute: TrackAttribute,
+        ) -> Result<&mut Self, ValidationError> {
+            attribute.validate()?;
+
             let mut key = "min_".to_string();
             key.push_str(&attribute.get_name());
             self.min_attribute.insert(key, attribute.get_value());
 
-            self
+            Ok(self)
         }
 
-        pub fn set_artist(&mut self, artist_id: &str) -> &mut Self {
-            self.seed
-                .push(("seed_artists".to_string(), artist_id.to_string()));
-            self.seed.truncate(5);
+        pub fn set_artist(&mut self, artist_id: &str) -> Result<&mut Self, ValidationError> {
+            self.push_seed("seed_artists", artist_id)
+        }
 
-            self
+        pub fn set_genre(&mut self, genre_id: &str) -> Result<&mut Self, ValidationError> {
+            self.push_seed("seed_genres", genre_id)
         }
 
-        pub fn set_genre(&mut self, genre_id: &str) -> &mut Self {
-            self.seed
-                .push(("seed_genres".to_string(), genre_id.to_string()));
-            self.seed.truncate(5);
+        pub fn set_checked_genre(
+            &mut self,
+            genre_id: &str,
+            genre_seeds: &GenreSeeds,
+        ) -> Result<&mut Self, ValidationError> {
+            let genre_id = genre_seeds.validate(genre_id)?;
 
-            self
+            self.set_genre(&genre_id)
         }
 
-        pub fn set_track(&mut self, track_id: &str) -> &mut Self {
-            self.seed
-                .push(("seed_tracks".to_string(), track_id.to_string()));
-            self.seed.truncate(5);
-
-            self
+        pub fn set_track(&mut self, track_id: &str) -> Result<&mut Self, ValidationError> {
+            self.push_seed("seed_tracks", track_id)
         }
 
-        pub fn set_target_attribute(&mut self, attribute: TrackAttribute) -> &mut Self {
+        pub fn set_target_attribute(
+            &mut self,
+            attribute: TrackAttribute,
+        ) -> Result<&mut Self, ValidationError> {
+            attribute.validate()?;
+
             let mut key = "target_".to_string();
             key.push_str(&attribute.get_name());
             self.target_attribute.insert(key, attribute.get_value());
 
-            self
+            Ok(self)
+        }
+
+        fn push_seed(&mut self, key: &str, id: &str) -> Result<&mut Self, ValidationError> {
+            if self.seed.len() >= GetRecommendationsRequest::MAX_SEEDS {
+                return Err(ValidationError::TooManySeeds(self.seed.len() + 1));
+            }
+
+            self.seed.push((key.to_string(), id.to_string()));
+
+            Ok(self)
         }
     }
 
@@ -264,13 +324,17 @@ pub mod recommendation {
     }
 
     impl TrackAttribute {
+        pub fn validate(self) -> Result<(), ValidationError> {
+            super::TrackAttribute::from(self).validate()
+        }
+
         fn get_name(self) -> String {
             use self::TrackAttribute::*;
 
             match self {
                 Acousticness(_) => "acousticness".to_string(),
                 Danceability(_) => "danceability".to_string(),
-                DurationMs(_) => "Duration_ms".to_string(),
+                DurationMs(_) => "duration_ms".to_string(),
                 Energy(_) => "energy".to_string(),
                 Instrumentalness(_) => "instrumentalness".to_string(),
                 Key(_) => "key".to_string(),
@@ -308,6 +372,107 @@ pub mod recommendation {
     }
 }
 
+impl From<recommendation::TrackAttribute> for TrackAttribute {
+    fn from(attribute: recommendation::TrackAttribute) -> Self {
+        use self::recommendation::TrackAttribute as Filter;
+
+        match attribute {
+            Filter::Acousticness(v) => TrackAttribute::Acousticness(v),
+            Filter::Danceability(v) => TrackAttribute::Danceability(v),
+            Filter::DurationMs(v) => TrackAttribute::DurationMs(v),
+            Filter::Energy(v) => TrackAttribute::Energy(v),
+            Filter::Instrumentalness(v) => TrackAttribute::Instrumentalness(v),
+            Filter::Key(v) => TrackAttribute::Key(v),
+            Filter::Liveness(v) => TrackAttribute::Liveness(v),
+            Filter::Loudness(v) => TrackAttribute::Loudness(v),
+            Filter::Mode(v) => TrackAttribute::Mode(v),
+            Filter::Popularity(v) => TrackAttribute::Popularity(v),
+            Filter::Speechiness(v) => TrackAttribute::Speechiness(v),
+            Filter::Tempo(v) => TrackAttribute::Tempo(v),
+            Filter::TimeSignature(v) => TrackAttribute::TimeSignature(v),
+            Filter::Valence(v) => TrackAttribute::Valence(v),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct GenreSeeds {
+    pub genres: Vec<String>,
+}
+
+impl GenreSeeds {
+    const MAX_SUGGESTIONS: usize = 3;
+
+    pub fn normalize(genre: &str) -> String {
+        genre
+            .trim()
+            .to_lowercase()
+            .split(|c: char| c.is_whitespace() || c == '_')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    pub fn contains(&self, genre: &str) -> bool {
+        self.genres.iter().any(|g| g == genre)
+    }
+
+    pub fn validate(&self, genre: &str) -> Result<String, ValidationError> {
+        let normalized = GenreSeeds::normalize(genre);
+        if self.contains(&normalized) {
+            return Ok(normalized);
+        }
+
+        Err(ValidationError::UnknownGenre {
+            genre: genre.to_string(),
+            suggestions: self.suggest(genre),
+        })
+    }
+
+    pub fn suggest(&self, genre: &str) -> Vec<String> {
+        let normalized = GenreSeeds::normalize(genre);
+        let max_distance = (normalized.chars().count() / 4).max(2);
+
+        let mut candidates: Vec<(usize, &String)> = self
+            .genres
+            .iter()
+            .filter_map(|g| {
+                let distance = edit_distance(&normalized, g);
+                if distance <= max_distance {
+                    Some((distance, g))
+                } else if !normalized.is_empty() && g.contains(&normalized) {
+                    Some((max_distance + 1, g))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        candidates.sort();
+
+        candidates
+            .into_iter()
+            .take(GenreSeeds::MAX_SUGGESTIONS)
+            .map(|(_, g)| g.clone())
+            .collect()
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct GetCategoryRequest {
     pub id: String,
@@ -372,15 +537,59 @@ pub struct GetNewReleaseResponse {
 pub struct GetRecommendationsRequest {
     pub limit: Option<u32>,
     pub market: Option<CountryCode>,
-    pub seed_artists: Option<Vec<String>>,
+    pub seed_artists: Option<Vec<ArtistId>>,
     pub seed_genres: Option<Vec<String>>,
-    pub seed_tracks: Option<Vec<String>>,
+    pub seed_tracks: Option<Vec<TrackId>>,
     pub max_attributes: Option<Vec<TrackAttribute>>,
     pub min_attributes: Option<Vec<TrackAttribute>>,
     pub target_attributes: Option<Vec<TrackAttribute>>,
 }
 
 impl GetRecommendationsRequest {
+    pub const MAX_SEEDS: usize = 5;
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let seeds = self.seed_artists.as_ref().map_or(0, Vec::len)
+            + self.seed_genres.as_ref().map_or(0, Vec::len)
+            + self.seed_tracks.as_ref().map_or(0, Vec::len);
+        if seeds == 0 {
+            return Err(ValidationError::NoSeeds);
+        }
+        if seeds > GetRecommendationsRequest::MAX_SEEDS {
+            return Err(ValidationError::TooManySeeds(seeds));
+        }
+
+        if let Some(limit) = self.limit {
+            if !(1..=100).contains(&limit) {
+                return Err(ValidationError::LimitOutOfRange(limit));
+            }
+        }
+
+        let attributes = [
+            &self.max_attributes,
+            &self.min_attributes,
+            &self.target_attributes,
+        ];
+        for attribute in attributes.iter().filter_map(|a| a.as_ref()).flatten() {
+            attribute.validate()?;
+        }
+
+        for min in self.min_attributes.iter().flatten() {
+            let max = self
+                .max_attributes
+                .iter()
+                .flatten()
+                .find(|max| max.to_string() == min.to_string());
+            if let Some(max) = max {
+                if min.as_f64() > max.as_f64() {
+                    return Err(ValidationError::InvertedRange(min.to_string()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn get_query(&self) -> Vec<(String, String)> {
         let mut query = Vec::new();
         query.append(&mut self.get_seeds());
@@ -393,7 +602,7 @@ impl GetRecommendationsRequest {
         let mut seeds = Vec::new();
 
         if let Some(artists) = &self.seed_artists {
-            let s = artists.join(",");
+            let s = artists.iter().map(Id::id).collect::<Vec<_>>().join(",");
             seeds.push(("seed_artists".to_string(), s));
         }
 
@@ -403,7 +612,7 @@ impl GetRecommendationsRequest {
         }
 
         if let Some(tracks) = &self.seed_tracks {
-            let s = tracks.join(",");
+            let s = tracks.iter().map(Id::id).collect::<Vec<_>>().join(",");
             seeds.push(("seed_tracks".to_string(), s));
         }
 
@@ -415,21 +624,21 @@ impl GetRecommendationsRequest {
 
         if let Some(max_attributes) = &self.max_attributes {
             for attribute in max_attributes {
-                let s = format!("max_{}", attribute.to_string());
+                let s = format!("max_{}", attribute);
                 attributes.push((s, attribute.get_value()));
             }
         }
 
         if let Some(min_attributes) = &self.min_attributes {
             for attribute in min_attributes {
-                let s = format!("min_{}", attribute.to_string());
+                let s = format!("min_{}", attribute);
                 attributes.push((s, attribute.get_value()));
             }
         }
 
         if let Some(target_attributes) = &self.target_attributes {
             for attribute in target_attributes {
-                let s = format!("target_{}", attribute.to_string());
+                let s = format!("target_{}", attribute);
                 attributes.push((s, attribute.get_value()));
             }
         }
@@ -439,9 +648,9 @@ impl GetRecommendationsRequest {
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
-pub struct GetRecommendationsResponse {
+pub struct Recommendations {
     pub seeds: Vec<RecommendationSeed>,
-    pub tracks: Vec<SimpleTrack>,
+    pub tracks: Vec<Track>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -463,6 +672,56 @@ pub enum TrackAttribute {
 }
 
 impl TrackAttribute {
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            TrackAttribute::Acousticness(v)
+            | TrackAttribute::Danceability(v)
+            | TrackAttribute::Energy(v)
+            | TrackAttribute::Instrumentalness(v)
+            | TrackAttribute::Liveness(v)
+            | TrackAttribute::Loudness(v)
+            | TrackAttribute::Speechiness(v)
+            | TrackAttribute::Tempo(v)
+            | TrackAttribute::Valence(v) => f64::from(v),
+            TrackAttribute::DurationMs(v)
+            | TrackAttribute::Key(v)
+            | TrackAttribute::Mode(v)
+            | TrackAttribute::Popularity(v)
+            | TrackAttribute::TimeSignature(v) => f64::from(v),
+        }
+    }
+
+    pub fn range(&self) -> (f64, f64) {
+        match self {
+            TrackAttribute::Acousticness(_)
+            | TrackAttribute::Danceability(_)
+            | TrackAttribute::Energy(_)
+            | TrackAttribute::Instrumentalness(_)
+            | TrackAttribute::Liveness(_)
+            | TrackAttribute::Mode(_)
+            | TrackAttribute::Speechiness(_)
+            | TrackAttribute::Valence(_) => (0.0, 1.0),
+            TrackAttribute::Key(_) => (0.0, 11.0),
+            TrackAttribute::Popularity(_) => (0.0, 100.0),
+            TrackAttribute::TimeSignature(_) => (3.0, 7.0),
+            TrackAttribute::Loudness(_) => (-60.0, 0.0),
+            TrackAttribute::DurationMs(_) | TrackAttribute::Tempo(_) => (0.0, f64::MAX),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let (min, max) = self.range();
+        let value = self.as_f64();
+        if !value.is_finite() || value < min || value > max {
+            return Err(ValidationError::AttributeOutOfRange {
+                attribute: self.to_string(),
+                value: self.get_value(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn get_value(&self) -> String {
         match &self {
             TrackAttribute::Acousticness(v) => v.to_string(),
@@ -488,7 +747,7 @@ impl fmt::Display for TrackAttribute {
         let s = match self {
             TrackAttribute::Acousticness(_) => "acousticness",
             TrackAttribute::Danceability(_) => "danceability",
-            TrackAttribute::DurationMs(_) => "Duration_ms",
+            TrackAttribute::DurationMs(_) => "duration_ms",
             TrackAttribute::Energy(_) => "energy",
             TrackAttribute::Instrumentalness(_) => "instrumentalness",
             TrackAttribute::Key(_) => "key",
@@ -502,7 +761,7 @@ impl fmt::Display for TrackAttribute {
             TrackAttribute::Valence(_) => "valence",
         };
 
-        write!(f, "{}", s.to_string())
+        write!(f, "{}", s)
     }
 }
 
@@ -515,5 +774,14 @@ pub struct RecommendationSeed {
     pub id: String,
     pub initial_pool_size: u32,
     #[serde(rename = "type")]
-    pub object_type: String,
+    pub object_type: SeedType,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SeedType {
+    #[default]
+    Artist,
+    Genre,
+    Track,
 }
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use crate::object::Image;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Episode {
+    pub audio_preview_url: Option<String>,
+    pub description: String,
+    pub duration_ms: u32,
+    pub explicit: bool,
+    pub href: String,
+    pub id: String,
+    pub images: Vec<Image>,
+    pub is_externally_hosted: bool,
+    pub is_playable: Option<bool>,
+    pub languages: Vec<String>,
+    pub name: String,
+    pub release_date: String,
+    pub release_date_precision: String,
+    pub resume_point: Option<ResumePoint>,
+    pub show: Option<SimpleShow>,
+    #[serde(rename = "type")]
+    pub object_type: String,
+    pub uri: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ResumePoint {
+    pub fully_played: bool,
+    pub resume_position_ms: u32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SimpleShow {
+    pub available_markets: Option<Vec<String>>,
+    pub description: String,
+    pub explicit: bool,
+    pub href: String,
+    pub id: String,
+    pub images: Vec<Image>,
+    pub is_externally_hosted: Option<bool>,
+    pub languages: Vec<String>,
+    pub media_type: String,
+    pub name: String,
+    pub publisher: String,
+    #[serde(rename = "type")]
+    pub object_type: String,
+    pub uri: String,
+}
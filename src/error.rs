@@ -0,0 +1,231 @@
+use std::{error::Error, fmt, time::Duration};
+
+use serde::Deserialize;
+
+use crate::player::DeviceSelector;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ApiError {
+    RateLimited {
+        retry_after: Option<Duration>,
+    },
+    Status {
+        status: u16,
+        message: String,
+        reason: Option<String>,
+    },
+}
+
+impl ApiError {
+    pub(crate) fn from_body(status: u16, body: &str) -> Self {
+        match serde_json::from_str::<ErrorResponse>(body) {
+            Ok(response) => ApiError::Status {
+                status,
+                message: response.error.message,
+                reason: response.error.reason,
+            },
+            Err(_) => ApiError::Status {
+                status,
+                message: body.to_string(),
+                reason: None,
+            },
+        }
+    }
+
+    pub fn is_snapshot_conflict(&self) -> bool {
+        match self {
+            ApiError::Status { status, .. } if *status == 409 || *status == 412 => true,
+            ApiError::Status {
+                status: 400,
+                message,
+                ..
+            } => message.to_lowercase().contains("snapshot"),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorObject,
+}
+
+#[derive(Deserialize)]
+struct ErrorObject {
+    message: String,
+    reason: Option<String>,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(f, "rate limited, retry after {}s", retry_after.as_secs()),
+            ApiError::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            ApiError::Status {
+                status, message, ..
+            } => write!(f, "{} {}", status, message),
+        }
+    }
+}
+
+impl Error for ApiError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PlayerError {
+    NoActiveDevice,
+    DeviceNotFound(DeviceSelector),
+    PremiumRequired,
+    NoPreviousTrack,
+    NoNextTrack,
+    AlreadyPaused,
+    NotPaused,
+    AlreadyPlaying,
+    VolumeControlDisallowed,
+    Restricted { reason: String, message: String },
+}
+
+impl PlayerError {
+    pub fn from_reason(reason: &str, message: &str) -> Self {
+        match reason {
+            "NO_ACTIVE_DEVICE" => PlayerError::NoActiveDevice,
+            "PREMIUM_REQUIRED" => PlayerError::PremiumRequired,
+            "NO_PREV_TRACK" => PlayerError::NoPreviousTrack,
+            "NO_NEXT_TRACK" => PlayerError::NoNextTrack,
+            "ALREADY_PAUSED" => PlayerError::AlreadyPaused,
+            "NOT_PAUSED" => PlayerError::NotPaused,
+            "ALREADY_PLAYING" => PlayerError::AlreadyPlaying,
+            "VOLUME_CONTROL_DISALLOW" => PlayerError::VolumeControlDisallowed,
+            _ => PlayerError::Restricted {
+                reason: reason.to_string(),
+                message: message.to_string(),
+            },
+        }
+    }
+
+    pub(crate) fn from_api_error(error: Box<dyn Error>) -> Box<dyn Error> {
+        match error.downcast_ref::<ApiError>() {
+            Some(ApiError::Status {
+                message,
+                reason: Some(reason),
+                ..
+            }) => Box::new(PlayerError::from_reason(reason, message)),
+            _ => error,
+        }
+    }
+}
+
+impl fmt::Display for PlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayerError::NoActiveDevice => write!(f, "no active device"),
+            PlayerError::DeviceNotFound(selector) => write!(f, "no device matching {}", selector),
+            PlayerError::PremiumRequired => write!(f, "premium required"),
+            PlayerError::NoPreviousTrack => write!(f, "no previous track"),
+            PlayerError::NoNextTrack => write!(f, "no next track"),
+            PlayerError::AlreadyPaused => write!(f, "already paused"),
+            PlayerError::NotPaused => write!(f, "not paused"),
+            PlayerError::AlreadyPlaying => write!(f, "already playing"),
+            PlayerError::VolumeControlDisallowed => write!(f, "volume control disallowed"),
+            PlayerError::Restricted { reason, message } => {
+                write!(f, "restricted ({}): {}", reason, message)
+            }
+        }
+    }
+}
+
+impl Error for PlayerError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+    VolumeOutOfRange(u32),
+    PositionOutOfRange {
+        position_ms: u32,
+        duration_ms: u32,
+    },
+    EmptyUris,
+    TransferToActiveDevice,
+    InvalidContextUri(String),
+    OffsetOutOfRange {
+        position: u32,
+        len: usize,
+    },
+    OffsetNotInUris(String),
+    NoSeeds,
+    TooManySeeds(usize),
+    LimitOutOfRange(u32),
+    EmptyPlaylist,
+    AttributeOutOfRange {
+        attribute: String,
+        value: String,
+    },
+    InvertedRange(String),
+    UnknownGenre {
+        genre: String,
+        suggestions: Vec<String>,
+    },
+    UnsupportedVersion {
+        document: &'static str,
+        version: u64,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::VolumeOutOfRange(volume_percent) => {
+                write!(f, "volume {} is not between 0 and 100", volume_percent)
+            }
+            ValidationError::PositionOutOfRange {
+                position_ms,
+                duration_ms,
+            } => write!(
+                f,
+                "position {}ms is past the end of the item ({}ms)",
+                position_ms, duration_ms
+            ),
+            ValidationError::EmptyUris => write!(f, "at least one uri is required"),
+            ValidationError::TransferToActiveDevice => {
+                write!(f, "playback is already on the active device")
+            }
+            ValidationError::InvalidContextUri(uri) => {
+                write!(f, "invalid context uri: {}", uri)
+            }
+            ValidationError::OffsetOutOfRange { position, len } => {
+                write!(f, "offset {} is out of range for {} uris", position, len)
+            }
+            ValidationError::OffsetNotInUris(uri) => {
+                write!(f, "offset uri {} is not one of the uris", uri)
+            }
+            ValidationError::NoSeeds => write!(f, "at least one seed is required"),
+            ValidationError::TooManySeeds(seeds) => {
+                write!(f, "{} seeds given, at most 5 are allowed", seeds)
+            }
+            ValidationError::LimitOutOfRange(limit) => {
+                write!(f, "limit {} is not between 1 and 100", limit)
+            }
+            ValidationError::EmptyPlaylist => write!(f, "playlist size must be at least 1"),
+            ValidationError::AttributeOutOfRange { attribute, value } => {
+                write!(f, "{} {} is out of range", attribute, value)
+            }
+            ValidationError::InvertedRange(attribute) => {
+                write!(f, "min_{} is greater than max_{}", attribute, attribute)
+            }
+            ValidationError::UnknownGenre { genre, suggestions } if suggestions.is_empty() => {
+                write!(f, "unknown genre seed {}", genre)
+            }
+            ValidationError::UnknownGenre { genre, suggestions } => write!(
+                f,
+                "unknown genre seed {}, did you mean {}?",
+                genre,
+                suggestions.join(", ")
+            ),
+            ValidationError::UnsupportedVersion { document, version } => {
+                write!(f, "unsupported {} version {}", document, version)
+            }
+        }
+    }
+}
+
+impl Error for ValidationError {}
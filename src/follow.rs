@@ -1,11 +1,18 @@
-use std::error::Error;
+use std::{collections::HashSet, error::Error};
 
-use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{self, Stream, StreamExt};
 use reqwest::header::CONTENT_TYPE;
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::{artist::Artist, object::CursorPagingObject, RequestClient};
+use crate::{
+    artist::Artist,
+    batch::{self, BatchResult, Endpoint},
+    id::{self, ArtistId, Id, IdError, PlaylistId, UserId},
+    object::{CursorPagingObject, PagingObject},
+    playlist::SimplePlaylist,
+    RequestClient,
+};
 
 #[derive(Clone, Debug, Default)]
 pub struct FollowClient {
@@ -20,123 +27,129 @@ impl FollowClient {
     }
 
     pub async fn is_following_artist(
-        &mut self,
-        request: CheckFollowRequest,
+        &self,
+        request: CheckFollowRequest<ArtistId>,
     ) -> Result<Vec<bool>, Box<dyn Error>> {
-        self.is_following(ObjectType::Artist, request.ids).await
+        self.is_following(ObjectType::Artist, id::into_strings(request.ids))
+            .await
     }
 
     pub async fn is_following_user(
-        &mut self,
-        request: CheckFollowRequest,
+        &self,
+        request: CheckFollowRequest<UserId>,
     ) -> Result<Vec<bool>, Box<dyn Error>> {
-        self.is_following(ObjectType::User, request.ids).await
+        self.is_following(ObjectType::User, id::into_strings(request.ids))
+            .await
     }
 
-    fn is_following(
-        &mut self,
+    async fn is_following(
+        &self,
         object_type: ObjectType,
-        mut ids: Vec<String>,
-    ) -> BoxFuture<'_, Result<Vec<bool>, Box<dyn Error>>> {
-        async move {
-            let mut results = Vec::new();
-            if ids.len() > 50 {
-                let drained: Vec<String> = ids.drain(..50).collect();
-                results.append(&mut self.is_following(object_type, drained).await?);
-                results.append(&mut self.is_following(object_type, ids.clone()).await?);
-
-                return Ok(results);
-            }
+        ids: Vec<String>,
+    ) -> Result<Vec<bool>, Box<dyn Error>> {
+        let results = batch::run(Endpoint::Follow, ids, |_, ids| {
+            self.request_is_following(object_type, ids)
+        })
+        .await
+        .into_items()?;
 
-            let params = [("type", object_type.to_string()), ("ids", ids.join(","))];
-            let builder = reqwest::Client::new()
-                .get("https://api.spotify.com/v1/me/following/contains")
-                .query(&params);
+        Ok(results)
+    }
 
-            let response = self.client.send(builder).await?.unwrap();
+    async fn request_is_following(
+        &self,
+        object_type: ObjectType,
+        ids: Vec<String>,
+    ) -> Result<Vec<bool>, Box<dyn Error>> {
+        let params = [("type", object_type.to_string()), ("ids", ids.join(","))];
+        let builder = reqwest::Client::new()
+            .get("https://api.spotify.com/v1/me/following/contains")
+            .query(&params);
 
-            results.append(&mut response.json().await?);
+        let response = self.client.send(builder).await?.unwrap();
 
-            Ok(results)
-        }
-        .boxed()
+        Ok(response.json().await?)
     }
 
-    pub fn is_users_following_playlist(
-        &mut self,
-        mut request: CheckUserFollowPlaylistRequest,
-    ) -> BoxFuture<'_, Result<Vec<bool>, Box<dyn Error>>> {
-        async move {
-            let url = format!(
-                "https://api.spotify.com/v1/playlists/{}/followers/contains",
-                request.playlist_id
-            );
-
-            let mut results = Vec::new();
-            if request.user_ids.len() > 5 {
-                let user_ids: Vec<String> = request.user_ids.drain(..5).collect();
-
-                let r = CheckUserFollowPlaylistRequest {
-                    playlist_id: request.playlist_id.clone(),
-                    user_ids,
-                };
-
-                results.append(&mut self.is_users_following_playlist(r).await?);
-                results.append(&mut self.is_users_following_playlist(request.clone()).await?);
-
-                return Ok(results);
-            }
+    pub async fn is_users_following_playlist(
+        &self,
+        request: CheckUserFollowPlaylistRequest,
+    ) -> Result<Vec<bool>, Box<dyn Error>> {
+        let playlist_id = &request.playlist_id;
+        let user_ids = id::into_strings(request.user_ids);
+        let results = batch::run(Endpoint::PlaylistFollowers, user_ids, |_, ids| {
+            self.request_is_users_following_playlist(playlist_id, ids)
+        })
+        .await
+        .into_items()?;
+
+        Ok(results)
+    }
 
-            let builder = reqwest::Client::new()
-                .get(&url)
-                .query(&[("ids", request.user_ids.join(","))]);
+    async fn request_is_users_following_playlist(
+        &self,
+        playlist_id: &PlaylistId,
+        user_ids: Vec<String>,
+    ) -> Result<Vec<bool>, Box<dyn Error>> {
+        let url = format!(
+            "https://api.spotify.com/v1/playlists/{}/followers/contains",
+            playlist_id
+        );
 
-            let response = self.client.send(builder).await?.unwrap();
+        let builder = reqwest::Client::new()
+            .get(&url)
+            .query(&[("ids", user_ids.join(","))]);
 
-            let mut values: Vec<bool> = response.json().await?;
-            results.append(&mut values);
+        let response = self.client.send(builder).await?.unwrap();
 
-            Ok(results)
-        }
-        .boxed()
+        Ok(response.json().await?)
     }
 
-    pub async fn follow_artists(&mut self, request: FollowRequest) -> Result<(), Box<dyn Error>> {
-        self.follow(ObjectType::Artist, request.ids).await
+    pub async fn follow_artists(
+        &self,
+        request: FollowRequest<ArtistId>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.follow(ObjectType::Artist, id::into_strings(request.ids))
+            .await
     }
 
-    pub async fn follow_users(&mut self, request: FollowRequest) -> Result<(), Box<dyn Error>> {
-        self.follow(ObjectType::User, request.ids).await
+    pub async fn follow_users(&self, request: FollowRequest<UserId>) -> Result<(), Box<dyn Error>> {
+        self.follow(ObjectType::User, id::into_strings(request.ids))
+            .await
     }
 
-    fn follow(
-        &mut self,
+    async fn follow(
+        &self,
         object_type: ObjectType,
-        mut ids: Vec<String>,
-    ) -> BoxFuture<'_, Result<(), Box<dyn Error>>> {
-        async move {
-            if ids.len() > 50 {
-                self.follow(object_type, ids.drain(..50).collect()).await?;
-                self.follow(object_type, ids.clone()).await?;
-
-                return Ok(());
-            }
+        ids: Vec<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        batch::run(Endpoint::Follow, ids, |_, ids| {
+            self.request_follow(object_type, ids)
+        })
+        .await
+        .into_result()?;
+
+        Ok(())
+    }
 
-            let builder = reqwest::Client::new()
-                .put("https://api.spotify.com/v1/me/following")
-                .header(CONTENT_TYPE, "application/json")
-                .query(&[("type", object_type.to_string())])
-                .json(&json!({ "ids": ids }));
+    async fn request_follow(
+        &self,
+        object_type: ObjectType,
+        ids: Vec<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let builder = reqwest::Client::new()
+            .put("https://api.spotify.com/v1/me/following")
+            .header(CONTENT_TYPE, "application/json")
+            .query(&[("type", object_type.to_string())])
+            .json(&json!({ "ids": ids }));
 
-            self.client.send(builder).await?.unwrap();
+        self.client.send(builder).await?.unwrap();
 
-            Ok(())
-        }
-        .boxed()
+        Ok(())
     }
 
     pub async fn follow_playlist(
-        &mut self,
+        &self,
         request: FollowPlaylistRequest,
     ) -> Result<(), Box<dyn Error>> {
         let url = format!(
@@ -156,7 +169,7 @@ impl FollowClient {
     }
 
     pub async fn get_followed_artists(
-        &mut self,
+        &self,
         request: GetUserFollowedArtistRequest,
     ) -> Result<GetUserFollowedArtistResponse, Box<dyn Error>> {
         let mut query = Vec::new();
@@ -173,6 +186,7 @@ impl FollowClient {
 
         let response = self
             .client
+            .clone()
             .set_limit(request.limit)
             .send(builder)
             .await?
@@ -181,45 +195,150 @@ impl FollowClient {
         Ok(response.json().await?)
     }
 
+    pub fn followed_artists_stream(
+        &self,
+        request: GetUserFollowedArtistRequest,
+    ) -> impl Stream<Item = Result<Artist, Box<dyn Error>>> + '_ {
+        stream::unfold(Some(request), move |request| async move {
+            let request = request?;
+
+            match self.get_followed_artists(request.clone()).await {
+                Ok(response) => {
+                    let artists = response.artists;
+                    let after = artists.cursors.and_then(|cursors| cursors.after);
+                    let next = match (artists.next, after) {
+                        (Some(_), Some(after)) if !artists.items.is_empty() => {
+                            Some(GetUserFollowedArtistRequest {
+                                after: Some(after),
+                                ..request
+                            })
+                        }
+                        _ => None,
+                    };
+
+                    Some((artists.items.into_iter().map(Ok).collect(), next))
+                }
+                Err(error) => Some((vec![Err(error)], None)),
+            }
+        })
+        .flat_map(stream::iter)
+    }
+
+    pub async fn get_user_playlists(
+        &self,
+        request: GetFollowedPlaylistsRequest,
+    ) -> Result<PagingObject<SimplePlaylist>, Box<dyn Error>> {
+        let url = format!(
+            "https://api.spotify.com/v1/users/{}/playlists",
+            request.user_id
+        );
+
+        let builder = reqwest::Client::new().get(&url);
+
+        let response = self
+            .client
+            .clone()
+            .set_limit(request.limit)
+            .set_offset(request.offset)
+            .send(builder)
+            .await?
+            .unwrap();
+
+        Ok(response.json().await?)
+    }
+
+    pub fn followed_playlists_stream(
+        &self,
+        request: GetFollowedPlaylistsRequest,
+    ) -> impl Stream<Item = Result<SimplePlaylist, Box<dyn Error>>> + '_ {
+        stream::unfold(Some(request), move |request| async move {
+            let request = request?;
+
+            match self.get_user_playlists(request.clone()).await {
+                Ok(page) => {
+                    let fetched = page.items.len() as u32;
+                    let next = match page.next {
+                        Some(_) if fetched > 0 => Some(GetFollowedPlaylistsRequest {
+                            offset: Some(request.offset.unwrap_or(0) + fetched),
+                            ..request.clone()
+                        }),
+                        _ => None,
+                    };
+                    let playlists = followed_playlists(page.items, &request.user_id);
+
+                    Some((playlists.into_iter().map(Ok).collect(), next))
+                }
+                Err(error) => Some((vec![Err(error)], None)),
+            }
+        })
+        .flat_map(stream::iter)
+    }
+
+    pub async fn follow_artists_bulk(&self, request: BulkFollowRequest) -> BatchResult<()> {
+        let ids = id::into_strings(request.ids);
+
+        batch::run(Endpoint::Follow, ids, |_, ids| {
+            self.request_follow(ObjectType::Artist, ids)
+        })
+        .await
+    }
+
+    pub async fn unfollow_artists_bulk(&self, request: BulkFollowRequest) -> BatchResult<()> {
+        let ids = id::into_strings(request.ids);
+
+        batch::run(Endpoint::Follow, ids, |_, ids| {
+            self.request_unfollow(ObjectType::Artist, ids)
+        })
+        .await
+    }
+
     pub async fn unfollow_artists(
-        &mut self,
-        request: UnfollowRequest,
+        &self,
+        request: UnfollowRequest<ArtistId>,
     ) -> Result<(), Box<dyn Error>> {
-        self.unfollow(ObjectType::Artist, request.ids).await
+        self.unfollow(ObjectType::Artist, id::into_strings(request.ids))
+            .await
     }
 
-    pub async fn unfollow_users(&mut self, request: UnfollowRequest) -> Result<(), Box<dyn Error>> {
-        self.unfollow(ObjectType::User, request.ids).await
+    pub async fn unfollow_users(
+        &self,
+        request: UnfollowRequest<UserId>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.unfollow(ObjectType::User, id::into_strings(request.ids))
+            .await
     }
 
-    fn unfollow(
-        &mut self,
+    async fn unfollow(
+        &self,
         object_type: ObjectType,
-        mut ids: Vec<String>,
-    ) -> BoxFuture<'_, Result<(), Box<dyn Error>>> {
-        async move {
-            if ids.len() > 50 {
-                let drained: Vec<String> = ids.drain(..50).collect();
-                self.unfollow(object_type.clone(), drained).await?;
-                self.unfollow(object_type, ids.clone()).await?;
-
-                return Ok(());
-            }
+        ids: Vec<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        batch::run(Endpoint::Follow, ids, |_, ids| {
+            self.request_unfollow(object_type, ids)
+        })
+        .await
+        .into_result()?;
 
-            let builder = reqwest::Client::new()
-                .delete("https://api.spotify.com/v1/me/following")
-                .query(&[("type", object_type.to_string())])
-                .json(&json!({ "ids": ids }));
+        Ok(())
+    }
 
-            self.client.send(builder).await?.unwrap();
+    async fn request_unfollow(
+        &self,
+        object_type: ObjectType,
+        ids: Vec<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let builder = reqwest::Client::new()
+            .delete("https://api.spotify.com/v1/me/following")
+            .query(&[("type", object_type.to_string())])
+            .json(&json!({ "ids": ids }));
 
-            Ok(())
-        }
-        .boxed()
+        self.client.send(builder).await?.unwrap();
+
+        Ok(())
     }
 
     pub async fn unfollow_playlist(
-        &mut self,
+        &self,
         request: UnfollowPlaylistRequest,
     ) -> Result<(), Box<dyn Error>> {
         let url = format!(
@@ -235,28 +354,62 @@ impl FollowClient {
     }
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct CheckFollowRequest {
-    pub ids: Vec<String>,
+pub fn followed_playlists(playlists: Vec<SimplePlaylist>, user_id: &UserId) -> Vec<SimplePlaylist> {
+    playlists
+        .into_iter()
+        .filter(|playlist| playlist.owner.id != user_id.id())
+        .collect()
 }
 
 #[derive(Clone, Debug, Default)]
+pub struct CheckFollowRequest<T> {
+    pub ids: Vec<T>,
+}
+
+#[derive(Clone, Debug)]
 pub struct CheckUserFollowPlaylistRequest {
-    pub playlist_id: String,
-    pub user_ids: Vec<String>,
+    pub playlist_id: PlaylistId,
+    pub user_ids: Vec<UserId>,
 }
 
 #[derive(Clone, Debug, Default)]
-pub struct FollowRequest {
-    pub ids: Vec<String>,
+pub struct FollowRequest<T> {
+    pub ids: Vec<T>,
 }
 
 #[derive(Clone, Debug, Default)]
+pub struct BulkFollowRequest {
+    pub ids: Vec<ArtistId>,
+}
+
+impl BulkFollowRequest {
+    pub fn from_artists(artists: &[Artist]) -> Result<Self, IdError> {
+        let mut seen = HashSet::new();
+        let mut ids = Vec::new();
+        for artist in artists {
+            let id: ArtistId = artist.id.parse()?;
+            if seen.insert(id.clone()) {
+                ids.push(id);
+            }
+        }
+
+        Ok(BulkFollowRequest { ids })
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct FollowPlaylistRequest {
-    pub id: String,
+    pub id: PlaylistId,
     pub public: Option<bool>,
 }
 
+#[derive(Clone, Debug)]
+pub struct GetFollowedPlaylistsRequest {
+    pub user_id: UserId,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct GetUserFollowedArtistRequest {
     pub object_type: ObjectType,
@@ -270,17 +423,18 @@ pub struct GetUserFollowedArtistResponse {
 }
 
 #[derive(Clone, Debug, Default)]
-pub struct UnfollowRequest {
-    pub ids: Vec<String>,
+pub struct UnfollowRequest<T> {
+    pub ids: Vec<T>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct UnfollowPlaylistRequest {
-    pub id: String,
+    pub id: PlaylistId,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
 pub enum ObjectType {
+    #[default]
     Artist,
     User,
 }
@@ -293,9 +447,3 @@ impl std::fmt::Display for ObjectType {
         }
     }
 }
-
-impl Default for ObjectType {
-    fn default() -> Self {
-        ObjectType::Artist
-    }
-}
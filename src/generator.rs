@@ -0,0 +1,313 @@
+use std::{collections::HashSet, error::Error};
+
+use isocountry::CountryCode;
+
+use crate::{
+    browse::{BrowseClient, GetRecommendationsRequest, RecommendationSeed, TrackAttribute},
+    error::ValidationError,
+    id::{ArtistId, Id, PlayableId, PlaylistId, TrackId, UserId},
+    library::{LibraryClient, LibraryIndex},
+    personalization::{GetTopRequest, PersonalizationClient, TimeRange},
+    player::{GetCurrentlyRequest, PlayerClient},
+    playlist::{
+        AddItemsRequest, CreatePlaylistRequest, GetPlaylistTracksRequest, Playlist, PlaylistClient,
+    },
+    track::Track,
+};
+
+pub const MAX_ATTEMPTS: usize = 5;
+
+#[derive(Clone, Debug)]
+pub enum SeedStrategy {
+    TopTracks { time_range: TimeRange, count: u32 },
+    Artist(ArtistId),
+    Genre(String),
+    CurrentTrack,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Seeds {
+    pub artists: Vec<ArtistId>,
+    pub genres: Vec<String>,
+    pub tracks: Vec<TrackId>,
+}
+
+impl Seeds {
+    pub fn len(&self) -> usize {
+        self.artists.len() + self.genres.len() + self.tracks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn dedup(&mut self) {
+        dedup(&mut self.artists);
+        dedup(&mut self.genres);
+        dedup(&mut self.tracks);
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.len() > GetRecommendationsRequest::MAX_SEEDS {
+            return Err(ValidationError::TooManySeeds(self.len()));
+        }
+
+        Ok(())
+    }
+}
+
+fn dedup<T: Clone + PartialEq>(values: &mut Vec<T>) {
+    let mut unique: Vec<T> = Vec::with_capacity(values.len());
+    for value in values.drain(..) {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+
+    *values = unique;
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TrackPicker {
+    size: usize,
+    seen: HashSet<String>,
+    tracks: Vec<Track>,
+}
+
+impl TrackPicker {
+    pub fn new<I: IntoIterator<Item = String>>(size: usize, seen: I) -> Self {
+        TrackPicker {
+            size,
+            seen: seen.into_iter().collect(),
+            tracks: Vec::new(),
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.tracks.len() >= self.size
+    }
+
+    pub fn exclude<I: IntoIterator<Item = String>>(&mut self, ids: I) {
+        self.seen.extend(ids);
+    }
+
+    pub fn candidates(&mut self, tracks: Vec<Track>) -> Vec<Track> {
+        tracks
+            .into_iter()
+            .filter(|track| self.seen.insert(track.id.clone()))
+            .collect()
+    }
+
+    pub fn extend<I: IntoIterator<Item = Track>>(&mut self, tracks: I) {
+        let remaining = self.size.saturating_sub(self.tracks.len());
+        self.tracks.extend(tracks.into_iter().take(remaining));
+    }
+
+    pub fn into_tracks(self) -> Vec<Track> {
+        self.tracks
+    }
+}
+
+fn non_empty<T: Clone>(values: &[T]) -> Option<Vec<T>> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.to_vec())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GeneratePlaylistRequest {
+    pub user_id: UserId,
+    pub name: String,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    pub seeds: Vec<SeedStrategy>,
+    pub max_attributes: Vec<TrackAttribute>,
+    pub min_attributes: Vec<TrackAttribute>,
+    pub target_attributes: Vec<TrackAttribute>,
+    pub size: usize,
+    pub exclude_playlists: Vec<PlaylistId>,
+    pub exclude_library: bool,
+    pub market: Option<CountryCode>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GeneratePlaylistResponse {
+    pub playlist: Playlist,
+    pub tracks: Vec<Track>,
+    pub seeds: Vec<RecommendationSeed>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PlaylistGenerator {
+    browse: BrowseClient,
+    library: LibraryIndex,
+    personalization: PersonalizationClient,
+    player: PlayerClient,
+    playlist: PlaylistClient,
+}
+
+impl PlaylistGenerator {
+    pub fn new(access_token: &str, refresh_token: &str) -> Self {
+        PlaylistGenerator {
+            browse: BrowseClient::new(access_token, refresh_token),
+            library: LibraryIndex::new(LibraryClient::new(access_token, refresh_token)),
+            personalization: PersonalizationClient::new(access_token, refresh_token),
+            player: PlayerClient::new(access_token, refresh_token),
+            playlist: PlaylistClient::new(access_token, refresh_token),
+        }
+    }
+
+    pub async fn resolve_seeds(
+        &self,
+        strategies: &[SeedStrategy],
+    ) -> Result<Seeds, Box<dyn Error>> {
+        let mut seeds = Seeds::default();
+
+        for strategy in strategies {
+            match strategy {
+                SeedStrategy::TopTracks { time_range, count } => {
+                    let tracks = self
+                        .personalization
+                        .get_top_tracks(GetTopRequest {
+                            limit: Some(*count),
+                            time_range: Some(*time_range),
+                            ..Default::default()
+                        })
+                        .await?
+                        .items;
+
+                    for track in tracks {
+                        seeds.tracks.push(track.id.parse()?);
+                    }
+                }
+                SeedStrategy::Artist(id) => seeds.artists.push(id.clone()),
+                SeedStrategy::Genre(genre) => seeds.genres.push(genre.clone()),
+                SeedStrategy::CurrentTrack => {
+                    let track = self
+                        .player
+                        .get_currently_playing_track(GetCurrentlyRequest::default())
+                        .await?
+                        .and_then(|current| current.get_track())
+                        .ok_or("no track is currently playing")?;
+
+                    seeds.tracks.push(track.id.parse()?);
+                }
+            }
+        }
+
+        seeds.dedup();
+        seeds.validate()?;
+
+        Ok(seeds)
+    }
+
+    pub async fn generate(
+        &self,
+        request: GeneratePlaylistRequest,
+    ) -> Result<GeneratePlaylistResponse, Box<dyn Error>> {
+        if request.size == 0 {
+            return Err(ValidationError::EmptyPlaylist.into());
+        }
+
+        let seeds = self.resolve_seeds(&request.seeds).await?;
+
+        let mut picker = TrackPicker::new(
+            request.size,
+            seeds.tracks.iter().map(|id| id.id().to_string()),
+        );
+        for playlist_id in &request.exclude_playlists {
+            let tracks = self
+                .playlist
+                .get_all_tracks(GetPlaylistTracksRequest {
+                    playlist_id: playlist_id.clone(),
+                    limit: None,
+                    offset: None,
+                    market: request.market,
+                })
+                .await?;
+            picker.exclude(tracks.into_iter().map(|item| item.track.id));
+        }
+
+        let recommendations_request = GetRecommendationsRequest {
+            limit: Some(100),
+            market: request.market,
+            seed_artists: non_empty(&seeds.artists),
+            seed_genres: non_empty(&seeds.genres),
+            seed_tracks: non_empty(&seeds.tracks),
+            max_attributes: non_empty(&request.max_attributes),
+            min_attributes: non_empty(&request.min_attributes),
+            target_attributes: non_empty(&request.target_attributes),
+        };
+
+        let mut recommendation_seeds = Vec::new();
+        for _ in 0..MAX_ATTEMPTS {
+            if picker.is_full() {
+                break;
+            }
+
+            let recommendations = self
+                .browse
+                .get_recommendations(recommendations_request.clone())
+                .await?;
+            recommendation_seeds = recommendations.seeds;
+
+            let candidates = picker.candidates(recommendations.tracks);
+            if candidates.is_empty() {
+                break;
+            }
+
+            let saved = if request.exclude_library {
+                let ids = candidates
+                    .iter()
+                    .map(|track| track.id.parse())
+                    .collect::<Result<Vec<TrackId>, _>>()?;
+                self.library.is_saved_tracks(&ids).await?
+            } else {
+                vec![false; candidates.len()]
+            };
+
+            picker.extend(
+                candidates
+                    .into_iter()
+                    .zip(saved)
+                    .filter(|(_, saved)| !saved)
+                    .map(|(track, _)| track),
+            );
+        }
+        let tracks = picker.into_tracks();
+
+        let playlist = self
+            .playlist
+            .create_playlist(CreatePlaylistRequest {
+                user_id: request.user_id,
+                name: request.name,
+                public: request.public,
+                collaborative: None,
+                description: request.description,
+            })
+            .await?;
+
+        if !tracks.is_empty() {
+            let uris = tracks
+                .iter()
+                .map(|track| track.id.parse().map(PlayableId::Track))
+                .collect::<Result<Vec<PlayableId>, _>>()?;
+
+            self.playlist
+                .add_items(AddItemsRequest {
+                    playlist_id: playlist.id.parse()?,
+                    uris,
+                    position: None,
+                })
+                .await?;
+        }
+
+        Ok(GeneratePlaylistResponse {
+            playlist,
+            tracks,
+            seeds: recommendation_seeds,
+        })
+    }
+}
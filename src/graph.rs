@@ -0,0 +1,219 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    future::Future,
+};
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    artist::{Artist, ArtistClient, GetArtistListRequest, GetRelatedArtistRequest},
+    batch,
+    id::ArtistId,
+};
+
+#[derive(Clone, Debug)]
+pub struct ExploreRelatedRequest {
+    pub seeds: Vec<ArtistId>,
+    pub depth: usize,
+    pub concurrency: usize,
+    pub max_nodes: Option<usize>,
+}
+
+impl Default for ExploreRelatedRequest {
+    fn default() -> Self {
+        ExploreRelatedRequest {
+            seeds: Vec::new(),
+            depth: 1,
+            concurrency: batch::CONCURRENCY,
+            max_nodes: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ArtistNode {
+    pub id: String,
+    pub name: String,
+    pub genres: Vec<String>,
+    pub popularity: Option<u32>,
+    pub depth: usize,
+}
+
+impl ArtistNode {
+    fn new(artist: Artist, depth: usize) -> Self {
+        ArtistNode {
+            id: artist.id,
+            name: artist.name,
+            genres: artist.genres,
+            popularity: artist.popularity,
+            depth,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Deserialize, Serialize)]
+pub struct ArtistEdge {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ArtistGraph {
+    pub nodes: Vec<ArtistNode>,
+    pub edges: Vec<ArtistEdge>,
+}
+
+impl ArtistGraph {
+    pub fn node(&self, id: &str) -> Option<&ArtistNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    pub fn neighbours(&self, id: &str) -> Vec<&ArtistNode> {
+        let nodes: HashMap<&str, &ArtistNode> = self
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+
+        self.edges
+            .iter()
+            .filter(|edge| edge.from == id)
+            .filter_map(|edge| nodes.get(edge.to.as_str()).copied())
+            .collect()
+    }
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph related_artists {\n");
+
+        for node in &self.nodes {
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\", depth={}, popularity={}];\n",
+                escape(&node.id),
+                escape(&node.name),
+                node.depth,
+                node.popularity.unwrap_or_default()
+            ));
+        }
+
+        for edge in &self.edges {
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\";\n",
+                escape(&edge.from),
+                escape(&edge.to)
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub(crate) async fn explore(
+    client: &ArtistClient,
+    request: ExploreRelatedRequest,
+) -> Result<ArtistGraph, Box<dyn Error>> {
+    let seeds = client
+        .get_artists(GetArtistListRequest {
+            ids: request.seeds.clone(),
+        })
+        .await?
+        .artists;
+
+    explore_with(seeds, &request, move |id| async move {
+        let response = client
+            .get_related_artists(GetRelatedArtistRequest { id })
+            .await?;
+
+        Ok(response.artists)
+    })
+    .await
+}
+
+pub async fn explore_with<F, Fut>(
+    seeds: Vec<Artist>,
+    request: &ExploreRelatedRequest,
+    related: F,
+) -> Result<ArtistGraph, Box<dyn Error>>
+where
+    F: Fn(ArtistId) -> Fut,
+    Fut: Future<Output = Result<Vec<Artist>, Box<dyn Error>>>,
+{
+    let mut graph = ArtistGraph::default();
+    let mut visited = HashSet::new();
+    let mut edges = HashSet::new();
+    let mut frontier = Vec::new();
+
+    let is_full = |graph: &ArtistGraph| {
+        request
+            .max_nodes
+            .is_some_and(|max_nodes| graph.nodes.len() >= max_nodes)
+    };
+
+    for artist in seeds {
+        if is_full(&graph) {
+            break;
+        }
+
+        if visited.insert(artist.id.clone()) {
+            frontier.push(artist.id.clone());
+            graph.nodes.push(ArtistNode::new(artist, 0));
+        }
+    }
+
+    let related = &related;
+    for depth in 0..request.depth {
+        let ids = frontier
+            .drain(..)
+            .map(|id| id.parse::<ArtistId>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let related: Vec<(ArtistId, Vec<Artist>)> = stream::iter(ids)
+            .map(|id| async move {
+                let artists = related(id.clone()).await?;
+
+                Ok::<_, Box<dyn Error>>((id, artists))
+            })
+            .buffered(request.concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        for (from, artists) in related {
+            for artist in artists {
+                let is_new = !visited.contains(&artist.id);
+                if is_new && is_full(&graph) {
+                    continue;
+                }
+
+                let edge = ArtistEdge {
+                    from: from.to_string(),
+                    to: artist.id.clone(),
+                };
+                if edges.insert(edge.clone()) {
+                    graph.edges.push(edge);
+                }
+
+                if is_new {
+                    visited.insert(artist.id.clone());
+                    frontier.push(artist.id.clone());
+                    graph.nodes.push(ArtistNode::new(artist, depth + 1));
+                }
+            }
+        }
+    }
+
+    Ok(graph)
+}
@@ -0,0 +1,396 @@
+use std::{collections::HashMap, convert::TryFrom, error::Error, fmt};
+
+use isocountry::CountryCode;
+
+use crate::{
+    id::{PlayableId, PlaylistId},
+    playlist::{
+        diff::PlaylistDiff, GetPlaylistRequest, GetPlaylistTracksRequest, PlaylistClient,
+        ReplaceRequest,
+    },
+    track::{AudioFeature, TrackClient},
+};
+
+pub const PITCH_CLASSES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+pub const BPM_TOLERANCE: f64 = 0.06;
+
+const REPLACE_LIMIT: usize = 100;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Mode {
+    Minor,
+    Major,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Key {
+    pub pitch_class: u8,
+    pub mode: Mode,
+}
+
+impl Key {
+    pub fn new(key: i32, mode: i32) -> Option<Self> {
+        let pitch_class = u8::try_from(key).ok().filter(|key| *key < 12)?;
+        let mode = match mode {
+            0 => Mode::Minor,
+            1 => Mode::Major,
+            _ => return None,
+        };
+
+        Some(Key { pitch_class, mode })
+    }
+
+    pub fn from_feature(feature: &AudioFeature) -> Option<Self> {
+        Key::new(feature.key, i32::try_from(feature.mode).ok()?)
+    }
+
+    pub fn pitch_class_name(&self) -> &'static str {
+        PITCH_CLASSES[self.pitch_class as usize]
+    }
+
+    pub fn camelot(&self) -> Camelot {
+        let major = match self.mode {
+            Mode::Major => self.pitch_class,
+            Mode::Minor => (self.pitch_class + 3) % 12,
+        };
+
+        Camelot {
+            number: (7 * major + 7) % 12 + 1,
+            mode: self.mode,
+        }
+    }
+
+    pub fn open_key(&self) -> String {
+        let camelot = self.camelot();
+        let number = (camelot.number + 4) % 12 + 1;
+        let suffix = match camelot.mode {
+            Mode::Minor => "m",
+            Mode::Major => "d",
+        };
+
+        format!("{}{}", number, suffix)
+    }
+
+    pub fn is_compatible(&self, other: &Key) -> bool {
+        self.camelot().distance(&other.camelot()) <= 1
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self.mode {
+            Mode::Minor => "minor",
+            Mode::Major => "major",
+        };
+
+        write!(f, "{} {}", self.pitch_class_name(), mode)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Camelot {
+    pub number: u8,
+    pub mode: Mode,
+}
+
+impl Camelot {
+    pub fn distance(&self, other: &Camelot) -> u32 {
+        let steps = (i32::from(self.number) - i32::from(other.number)).rem_euclid(12) as u32;
+        let steps = steps.min(12 - steps);
+
+        if self.mode == other.mode {
+            steps
+        } else {
+            steps + 1
+        }
+    }
+
+    pub fn neighbours(&self) -> [Camelot; 3] {
+        let up = self.number % 12 + 1;
+        let down = (self.number + 10) % 12 + 1;
+        let relative = match self.mode {
+            Mode::Minor => Mode::Major,
+            Mode::Major => Mode::Minor,
+        };
+
+        [
+            Camelot {
+                number: up,
+                mode: self.mode,
+            },
+            Camelot {
+                number: down,
+                mode: self.mode,
+            },
+            Camelot {
+                number: self.number,
+                mode: relative,
+            },
+        ]
+    }
+}
+
+impl fmt::Display for Camelot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = match self.mode {
+            Mode::Minor => "A",
+            Mode::Major => "B",
+        };
+
+        write!(f, "{}{}", self.number, letter)
+    }
+}
+
+pub fn tempo_distance(from: f64, to: f64) -> f64 {
+    if from <= 0.0 || to <= 0.0 {
+        return f64::INFINITY;
+    }
+
+    [to, to * 2.0, to / 2.0]
+        .iter()
+        .map(|to| (to - from).abs() / from)
+        .fold(f64::INFINITY, f64::min)
+}
+
+pub fn is_tempo_compatible(from: f64, to: f64, tolerance: f64) -> bool {
+    tempo_distance(from, to) <= tolerance
+}
+
+pub fn transition_cost(from: &AudioFeature, to: &AudioFeature) -> f64 {
+    let key = match (Key::from_feature(from), Key::from_feature(to)) {
+        (Some(from), Some(to)) => f64::from(from.camelot().distance(&to.camelot())),
+        _ => 6.0,
+    };
+    let tempo = tempo_distance(f64::from(from.tempo), f64::from(to.tempo)).min(1.0);
+
+    key + tempo / BPM_TOLERANCE
+}
+
+pub fn compatible_tracks<'a>(
+    feature: &AudioFeature,
+    candidates: &'a [AudioFeature],
+    bpm_tolerance: f64,
+) -> Vec<&'a AudioFeature> {
+    let key = match Key::from_feature(feature) {
+        Some(key) => key,
+        None => return Vec::new(),
+    };
+
+    candidates
+        .iter()
+        .filter(|candidate| candidate.id != feature.id)
+        .filter(|candidate| {
+            Key::from_feature(candidate).is_some_and(|other| key.is_compatible(&other))
+        })
+        .filter(|candidate| {
+            is_tempo_compatible(
+                f64::from(feature.tempo),
+                f64::from(candidate.tempo),
+                bpm_tolerance,
+            )
+        })
+        .collect()
+}
+
+pub fn mixing_order(features: &[AudioFeature]) -> Vec<usize> {
+    let mut remaining: Vec<usize> = (0..features.len()).collect();
+    let mut order = Vec::with_capacity(features.len());
+
+    if remaining.is_empty() {
+        return order;
+    }
+
+    let mut current = remaining.remove(0);
+    order.push(current);
+
+    while !remaining.is_empty() {
+        let (position, _) = remaining
+            .iter()
+            .enumerate()
+            .map(|(position, &index)| {
+                (
+                    position,
+                    transition_cost(&features[current], &features[index]),
+                )
+            })
+            .fold((0, f64::INFINITY), |best, candidate| {
+                if candidate.1 < best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+
+        current = remaining.remove(position);
+        order.push(current);
+    }
+
+    order
+}
+
+pub fn harmonic_order(
+    current: &[String],
+    features: &[AudioFeature],
+) -> (Vec<String>, Vec<AudioFeature>) {
+    let keyed: Vec<AudioFeature> = features
+        .iter()
+        .filter(|feature| Key::from_feature(feature).is_some())
+        .cloned()
+        .collect();
+    let ordered: Vec<AudioFeature> = mixing_order(&keyed)
+        .into_iter()
+        .map(|index| keyed[index].clone())
+        .collect();
+
+    let mut placed: HashMap<&str, usize> = HashMap::new();
+    for feature in &ordered {
+        *placed.entry(feature.uri.as_str()).or_insert(0) += 1;
+    }
+
+    let mut uris: Vec<String> = ordered.iter().map(|feature| feature.uri.clone()).collect();
+    for uri in current {
+        match placed.get_mut(uri.as_str()) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => uris.push(uri.clone()),
+        }
+    }
+
+    (uris, ordered)
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum WriteStrategy {
+    #[default]
+    Reorder,
+    Replace,
+}
+
+#[derive(Clone, Debug)]
+pub struct HarmonicOrderRequest {
+    pub playlist_id: PlaylistId,
+    pub strategy: WriteStrategy,
+    pub market: Option<CountryCode>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct HarmonicOrderResponse {
+    pub uris: Vec<String>,
+    pub features: Vec<AudioFeature>,
+    pub snapshot_id: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HarmonicMixer {
+    playlist: PlaylistClient,
+    track: TrackClient,
+}
+
+impl HarmonicMixer {
+    pub fn new(access_token: &str, refresh_token: &str) -> Self {
+        HarmonicMixer {
+            playlist: PlaylistClient::new(access_token, refresh_token),
+            track: TrackClient::new(access_token, refresh_token),
+        }
+    }
+
+    pub async fn order_playlist(
+        &self,
+        request: HarmonicOrderRequest,
+    ) -> Result<HarmonicOrderResponse, Box<dyn Error>> {
+        let items = self
+            .playlist
+            .get_all_tracks(GetPlaylistTracksRequest {
+                playlist_id: request.playlist_id.clone(),
+                limit: None,
+                offset: None,
+                market: request.market,
+            })
+            .await?;
+
+        let current: Vec<String> = items.iter().map(|item| item.track.uri.clone()).collect();
+        let features = self.track.get_playlist_features(&items).await?;
+        let (uris, ordered) = harmonic_order(&current, &features);
+
+        let snapshot_id = match request.strategy {
+            WriteStrategy::Reorder => self.reorder(&request.playlist_id, &current, &uris).await?,
+            WriteStrategy::Replace => {
+                if uris.len() > REPLACE_LIMIT {
+                    return Err(format!(
+                        "playlist {} has {} tracks, more than the {} that can be replaced at once",
+                        request.playlist_id,
+                        uris.len(),
+                        REPLACE_LIMIT
+                    )
+                    .into());
+                }
+
+                if items.iter().any(|item| item.is_local) {
+                    return Err(format!(
+                        "playlist {} contains local tracks and cannot be replaced",
+                        request.playlist_id
+                    )
+                    .into());
+                }
+
+                self.replace(&request.playlist_id, &uris).await?;
+                None
+            }
+        };
+
+        Ok(HarmonicOrderResponse {
+            uris,
+            features: ordered,
+            snapshot_id,
+        })
+    }
+
+    async fn reorder(
+        &self,
+        playlist_id: &PlaylistId,
+        current: &[String],
+        desired: &[String],
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        let diff = PlaylistDiff::new(current, desired);
+        if diff.is_empty() {
+            return Ok(None);
+        }
+
+        let playlist = self
+            .playlist
+            .get_playlist(GetPlaylistRequest {
+                playlist_id: playlist_id.clone(),
+                market: None,
+            })
+            .await?;
+
+        let snapshot_id = self
+            .playlist
+            .apply_diff(playlist_id, &diff, playlist.snapshot_id)
+            .await?;
+
+        Ok(Some(snapshot_id))
+    }
+
+    async fn replace(
+        &self,
+        playlist_id: &PlaylistId,
+        uris: &[String],
+    ) -> Result<(), Box<dyn Error>> {
+        let uris = uris
+            .iter()
+            .map(|uri| uri.parse())
+            .collect::<Result<Vec<PlayableId>, _>>()?;
+
+        self.playlist
+            .replace(ReplaceRequest {
+                playlist_id: playlist_id.clone(),
+                uris,
+            })
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::{
+    object::CursorPagingObject,
+    player::{GetRecentlyPlayedTracksRequest, PlayHistory, PlayerClient},
+};
+
+pub const RECENTLY_PLAYED_LIMIT: u32 = 50;
+
+type PlayKey = (DateTime<Utc>, String);
+
+#[derive(Clone, Debug)]
+pub struct HistoryLog {
+    path: PathBuf,
+}
+
+impl HistoryLog {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        HistoryLog {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn read(&self) -> Result<Vec<PlayHistory>, Box<dyn Error>> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut plays = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            plays.push(serde_json::from_str(&line)?);
+        }
+
+        Ok(plays)
+    }
+
+    pub fn last_played_at(&self) -> Result<Option<DateTime<Utc>>, Box<dyn Error>> {
+        Ok(self.read()?.iter().map(|play| play.played_at).max())
+    }
+
+    pub fn append(&self, plays: &[PlayHistory]) -> Result<Vec<PlayHistory>, Box<dyn Error>> {
+        let mut seen = self.keys()?;
+
+        self.append_unseen(&mut seen, plays)
+    }
+
+    pub async fn sync(&self, client: &PlayerClient) -> Result<Vec<PlayHistory>, Box<dyn Error>> {
+        let logged = self.read()?;
+        let mut after = logged.iter().map(|play| play.played_at).max();
+        let mut seen: HashSet<PlayKey> = logged.iter().map(HistoryLog::key).collect();
+        let mut added = Vec::new();
+
+        loop {
+            let request = GetRecentlyPlayedTracksRequest {
+                limit: Some(RECENTLY_PLAYED_LIMIT),
+                after,
+                ..Default::default()
+            };
+            let page = client.get_recently_played_tracks(request).await?;
+
+            let mut appended = self.append_unseen(&mut seen, &page.items)?;
+            if appended.is_empty() {
+                break;
+            }
+
+            after = HistoryLog::next_after(&page, &appended);
+            added.append(&mut appended);
+        }
+
+        Ok(added)
+    }
+
+    pub fn next_after(
+        page: &CursorPagingObject<PlayHistory>,
+        appended: &[PlayHistory],
+    ) -> Option<DateTime<Utc>> {
+        page.cursors
+            .as_ref()
+            .and_then(|cursors| cursors.after.as_ref())
+            .and_then(|after| after.parse().ok())
+            .and_then(|millis| Utc.timestamp_millis_opt(millis).single())
+            .or_else(|| appended.iter().map(|play| play.played_at).max())
+    }
+
+    fn keys(&self) -> Result<HashSet<PlayKey>, Box<dyn Error>> {
+        Ok(self.read()?.iter().map(HistoryLog::key).collect())
+    }
+
+    fn append_unseen(
+        &self,
+        seen: &mut HashSet<PlayKey>,
+        plays: &[PlayHistory],
+    ) -> Result<Vec<PlayHistory>, Box<dyn Error>> {
+        let mut plays: Vec<PlayHistory> = plays
+            .iter()
+            .filter(|play| seen.insert(HistoryLog::key(play)))
+            .cloned()
+            .collect();
+        plays.sort_by_key(|play| play.played_at);
+
+        if plays.is_empty() {
+            return Ok(plays);
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        for play in &plays {
+            writeln!(file, "{}", serde_json::to_string(play)?)?;
+        }
+
+        Ok(plays)
+    }
+
+    fn key(play: &PlayHistory) -> PlayKey {
+        (play.played_at, play.track.id.clone())
+    }
+}
@@ -0,0 +1,279 @@
+use std::{convert::TryFrom, error::Error, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+pub const LOCAL_URI_PREFIX: &str = "spotify:local:";
+
+pub fn is_local_uri(uri: &str) -> bool {
+    uri.trim().starts_with(LOCAL_URI_PREFIX)
+}
+
+pub trait Id {
+    const TYPE: &'static str;
+
+    fn id(&self) -> &str;
+
+    fn uri(&self) -> String {
+        format!("spotify:{}:{}", Self::TYPE, self.id())
+    }
+
+    fn url(&self) -> String {
+        format!("https://open.spotify.com/{}/{}", Self::TYPE, self.id())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IdError {
+    Empty,
+    InvalidId(String),
+    InvalidUri(String),
+    InvalidUrl(String),
+    LocalItem(String),
+    WrongType {
+        expected: &'static str,
+        found: String,
+    },
+}
+
+impl fmt::Display for IdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdError::Empty => write!(f, "empty Spotify ID"),
+            IdError::InvalidId(id) => write!(f, "invalid Spotify ID: {}", id),
+            IdError::InvalidUri(uri) => write!(f, "invalid Spotify URI: {}", uri),
+            IdError::InvalidUrl(url) => write!(f, "invalid Spotify URL: {}", url),
+            IdError::LocalItem(uri) => write!(f, "local files have no Spotify ID: {}", uri),
+            IdError::WrongType { expected, found } => {
+                write!(f, "expected a {} ID, found a {} ID", expected, found)
+            }
+        }
+    }
+}
+
+impl Error for IdError {}
+
+pub(crate) fn into_strings<T: Into<String>>(ids: Vec<T>) -> Vec<String> {
+    ids.into_iter().map(Into::into).collect()
+}
+
+fn split(s: &str) -> Result<(Option<String>, String), IdError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(IdError::Empty);
+    }
+
+    if s.starts_with("spotify:") {
+        let parts: Vec<&str> = s.split(':').collect();
+        return match parts.as_slice() {
+            ["spotify", object_type, id] => Ok((Some(object_type.to_string()), id.to_string())),
+            ["spotify", "user", _, "playlist", id] => {
+                Ok((Some("playlist".to_string()), id.to_string()))
+            }
+            _ => Err(IdError::InvalidUri(s.to_string())),
+        };
+    }
+
+    if s.starts_with("http://") || s.starts_with("https://") {
+        let url = reqwest::Url::parse(s).map_err(|_| IdError::InvalidUrl(s.to_string()))?;
+        match url.host_str() {
+            Some("open.spotify.com") | Some("play.spotify.com") => {}
+            _ => return Err(IdError::InvalidUrl(s.to_string())),
+        }
+
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|segment| !segment.is_empty()).collect())
+            .unwrap_or_default();
+
+        return match segments.as_slice() {
+            [.., object_type, id] => Ok((Some(object_type.to_string()), id.to_string())),
+            _ => Err(IdError::InvalidUrl(s.to_string())),
+        };
+    }
+
+    Ok((None, s.to_string()))
+}
+
+fn parse(s: &str, expected: &'static str) -> Result<String, IdError> {
+    let (object_type, id) = split(s)?;
+    if let Some(found) = object_type {
+        if found != expected {
+            return Err(IdError::WrongType { expected, found });
+        }
+    }
+
+    validate(&id, expected)?;
+
+    Ok(id)
+}
+
+fn validate(id: &str, object_type: &str) -> Result<(), IdError> {
+    let is_valid = if object_type == "user" {
+        !id.is_empty()
+            && !id
+                .chars()
+                .any(|c| c.is_whitespace() || c == ':' || c == '/' || c == '?' || c == '#')
+    } else {
+        id.len() == 22 && id.chars().all(|c| c.is_ascii_alphanumeric())
+    };
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(IdError::InvalidId(id.to_string()))
+    }
+}
+
+macro_rules! define_id {
+    ($name:ident, $object_type:expr) => {
+        #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+        #[serde(try_from = "String", into = "String")]
+        pub struct $name(String);
+
+        impl Id for $name {
+            const TYPE: &'static str = $object_type;
+
+            fn id(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = IdError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok($name(parse(s, $object_type)?))
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = IdError;
+
+            fn try_from(s: String) -> Result<Self, Self::Error> {
+                s.parse()
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = IdError;
+
+            fn try_from(s: &str) -> Result<Self, Self::Error> {
+                s.parse()
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+define_id!(AlbumId, "album");
+define_id!(ArtistId, "artist");
+define_id!(EpisodeId, "episode");
+define_id!(PlaylistId, "playlist");
+define_id!(ShowId, "show");
+define_id!(TrackId, "track");
+define_id!(UserId, "user");
+
+/// A track or episode. Local files (`spotify:local:` URIs) are not playable
+/// through the Web API and are rejected with `IdError::LocalItem`; callers
+/// check `is_local_uri` or `PlaylistTrack::is_local` to skip or keep them.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PlayableId {
+    Track(TrackId),
+    Episode(EpisodeId),
+}
+
+impl PlayableId {
+    pub fn id(&self) -> &str {
+        match self {
+            PlayableId::Track(id) => id.id(),
+            PlayableId::Episode(id) => id.id(),
+        }
+    }
+
+    pub fn uri(&self) -> String {
+        match self {
+            PlayableId::Track(id) => id.uri(),
+            PlayableId::Episode(id) => id.uri(),
+        }
+    }
+
+    pub fn url(&self) -> String {
+        match self {
+            PlayableId::Track(id) => id.url(),
+            PlayableId::Episode(id) => id.url(),
+        }
+    }
+}
+
+impl FromStr for PlayableId {
+    type Err = IdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_local_uri(s) {
+            return Err(IdError::LocalItem(s.trim().to_string()));
+        }
+
+        match split(s)? {
+            (Some(ref object_type), _) if object_type == EpisodeId::TYPE => {
+                Ok(PlayableId::Episode(s.parse()?))
+            }
+            (Some(ref object_type), _) if object_type != TrackId::TYPE => Err(IdError::WrongType {
+                expected: TrackId::TYPE,
+                found: object_type.to_string(),
+            }),
+            _ => Ok(PlayableId::Track(s.parse()?)),
+        }
+    }
+}
+
+impl TryFrom<String> for PlayableId {
+    type Error = IdError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<&str> for PlayableId {
+    type Error = IdError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<PlayableId> for String {
+    fn from(id: PlayableId) -> Self {
+        id.uri()
+    }
+}
+
+impl From<TrackId> for PlayableId {
+    fn from(id: TrackId) -> Self {
+        PlayableId::Track(id)
+    }
+}
+
+impl From<EpisodeId> for PlayableId {
+    fn from(id: EpisodeId) -> Self {
+        PlayableId::Episode(id)
+    }
+}
+
+impl fmt::Display for PlayableId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.uri())
+    }
+}
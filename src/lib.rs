@@ -1,27 +1,41 @@
-use std::error::Error;
+use std::{
+    error::Error,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
 use isocountry::CountryCode;
-use reqwest::{RequestBuilder, Response, StatusCode};
+use reqwest::{header::RETRY_AFTER, RequestBuilder, Response, StatusCode};
 
 pub mod album;
 pub mod artist;
 pub mod authentication;
+pub mod batch;
 pub mod browse;
+pub mod episode;
+pub mod error;
 pub mod follow;
+pub mod generator;
+pub mod graph;
+pub mod harmonic;
+pub mod history;
+pub mod id;
 pub mod library;
 pub mod object;
 pub mod personalization;
 pub mod player;
 pub mod playlist;
+pub mod profile;
+pub mod scrobble;
 pub mod search;
 pub mod track;
 pub mod user;
 use authentication::refresh_access_token;
+use error::ApiError;
 
 #[derive(Clone, Debug, Default)]
 pub struct RequestClient {
-    client: reqwest::Client,
-    access_token: String,
+    access_token: Arc<RwLock<String>>,
     refresh_token: String,
     offset: Option<u32>,
     limit: Option<u32>,
@@ -32,8 +46,7 @@ pub struct RequestClient {
 impl RequestClient {
     pub fn new(access_token: &str, refresh_token: &str) -> Self {
         RequestClient {
-            client: reqwest::Client::new(),
-            access_token: access_token.to_string(),
+            access_token: Arc::new(RwLock::new(access_token.to_string())),
             refresh_token: refresh_token.to_string(),
             offset: None,
             limit: None,
@@ -42,28 +55,37 @@ impl RequestClient {
         }
     }
 
-    pub fn set_offset(&mut self, offset: Option<u32>) -> &mut Self {
+    pub fn set_offset(mut self, offset: Option<u32>) -> Self {
         self.offset = offset;
         self
     }
 
-    pub fn set_limit(&mut self, limit: Option<u32>) -> &mut Self {
+    pub fn set_limit(mut self, limit: Option<u32>) -> Self {
         self.limit = limit;
         self
     }
 
-    pub fn set_market(&mut self, market: Option<CountryCode>) -> &mut Self {
+    pub fn set_market(mut self, market: Option<CountryCode>) -> Self {
         self.market = market;
         self
     }
 
-    pub fn set_country(&mut self, country: Option<CountryCode>) -> &mut Self {
+    pub fn set_country(mut self, country: Option<CountryCode>) -> Self {
         self.country = country;
         self
     }
 
+    pub fn access_token(&self) -> String {
+        self.access_token.read().unwrap().clone()
+    }
+
+    #[doc(hidden)]
+    pub fn set_access_token(&self, access_token: &str) {
+        *self.access_token.write().unwrap() = access_token.to_string();
+    }
+
     pub async fn send(
-        &mut self,
+        &self,
         mut builder: RequestBuilder,
     ) -> Result<Option<Response>, Box<dyn Error>> {
         if let Some(offset) = &self.offset {
@@ -83,7 +105,7 @@ impl RequestClient {
             let response = builder
                 .try_clone()
                 .unwrap()
-                .bearer_auth(&self.access_token)
+                .bearer_auth(self.access_token())
                 .send()
                 .await?;
 
@@ -94,13 +116,24 @@ impl RequestClient {
                 | StatusCode::OK => {
                     return Ok(Some(response));
                 }
+                StatusCode::TOO_MANY_REQUESTS => {
+                    let retry_after = response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| value.parse().ok())
+                        .map(Duration::from_secs);
+
+                    return Err(Box::new(ApiError::RateLimited { retry_after }));
+                }
                 StatusCode::UNAUTHORIZED => {
-                    self.access_token = refresh_access_token(&self.refresh_token).await?;
+                    let access_token = refresh_access_token(&self.refresh_token).await?;
+                    self.set_access_token(&access_token);
                 }
-                _ => {
-                    dbg!(&response);
-                    dbg!(&response.text().await?);
-                    return Ok(None);
+                status => {
+                    let body = response.text().await?;
+
+                    return Err(Box::new(ApiError::from_body(status.as_u16(), &body)));
                 }
             }
         }
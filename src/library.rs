@@ -1,28 +1,41 @@
-use std::error::Error;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use chrono::{DateTime, Utc};
-use futures::future::{BoxFuture, FutureExt};
 use isocountry::CountryCode;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::json;
 
-use crate::{album::Album, object::PagingObject, track::Track, RequestClient};
+use crate::{
+    album::Album,
+    batch::{self, Endpoint},
+    episode::SimpleShow,
+    id::{self, AlbumId, ShowId, TrackId},
+    object::{self, PagingObject},
+    track::Track,
+    RequestClient,
+};
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct SavedAlbum {
-    added_at: Option<DateTime<Utc>>,
-    album: Album,
+    pub added_at: Option<DateTime<Utc>>,
+    pub album: Album,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct SavedShow {
-    added_at: Option<DateTime<Utc>>,
+    pub added_at: Option<DateTime<Utc>>,
+    pub show: SimpleShow,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct SavedTrack {
-    added_at: Option<DateTime<Utc>>,
-    track: Track,
+    pub added_at: Option<DateTime<Utc>>,
+    pub track: Track,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -38,93 +51,91 @@ impl LibraryClient {
     }
 
     pub async fn is_saved_albums(
-        &mut self,
-        request: CheckSavedRequest,
+        &self,
+        request: CheckSavedRequest<AlbumId>,
     ) -> Result<Vec<bool>, Box<dyn Error>> {
-        self.is_saved(ObjectType::Albums, request.ids).await
+        self.is_saved(ObjectType::Albums, id::into_strings(request.ids))
+            .await
     }
 
     pub async fn is_saved_shows(
-        &mut self,
-        request: CheckSavedRequest,
+        &self,
+        request: CheckSavedRequest<ShowId>,
     ) -> Result<Vec<bool>, Box<dyn Error>> {
-        self.is_saved(ObjectType::Shows, request.ids).await
+        self.is_saved(ObjectType::Shows, id::into_strings(request.ids))
+            .await
     }
 
     pub async fn is_saved_tracks(
-        &mut self,
-        request: CheckSavedRequest,
+        &self,
+        request: CheckSavedRequest<TrackId>,
     ) -> Result<Vec<bool>, Box<dyn Error>> {
-        self.is_saved(ObjectType::Tracks, request.ids).await
+        self.is_saved(ObjectType::Tracks, id::into_strings(request.ids))
+            .await
     }
 
-    fn is_saved(
-        &mut self,
+    async fn is_saved(
+        &self,
         object_type: ObjectType,
-        mut ids: Vec<String>,
-    ) -> BoxFuture<'_, Result<Vec<bool>, Box<dyn Error>>> {
-        async move {
-            let url = format!(
-                "https://api.spotify.com/v1/me/{}/contains",
-                object_type.to_string()
-            );
-
-            let mut results = Vec::new();
-            if ids.len() > 50 {
-                results.append(
-                    &mut self
-                        .is_saved(object_type, ids.drain(..50).collect())
-                        .await?,
-                );
+        ids: Vec<String>,
+    ) -> Result<Vec<bool>, Box<dyn Error>> {
+        let results = batch::run(Endpoint::Library, ids, |_, ids| {
+            self.request_is_saved(object_type, ids)
+        })
+        .await
+        .into_items()?;
 
-                results.append(&mut self.is_saved(object_type, ids.clone()).await?);
+        Ok(results)
+    }
 
-                return Ok(results);
-            }
+    async fn request_is_saved(
+        &self,
+        object_type: ObjectType,
+        ids: Vec<String>,
+    ) -> Result<Vec<bool>, Box<dyn Error>> {
+        let url = format!("https://api.spotify.com/v1/me/{}/contains", object_type);
 
-            let builder = reqwest::Client::new()
-                .get(&url)
-                .query(&[("ids", ids.join(","))]);
+        let builder = reqwest::Client::new()
+            .get(&url)
+            .query(&[("ids", ids.join(","))]);
 
-            let response = self.client.send(builder).await?.unwrap();
-            results.append(&mut response.json().await?);
+        let response = self.client.send(builder).await?.unwrap();
 
-            Ok(results)
-        }
-        .boxed()
+        Ok(response.json().await?)
     }
 
     pub async fn get_saved_albums(
-        &mut self,
+        &self,
         request: GetSavedRequest,
     ) -> Result<PagingObject<SavedAlbum>, Box<dyn Error>> {
         self.get_saved(ObjectType::Albums, request).await
     }
 
     pub async fn get_saved_shows(
-        &mut self,
+        &self,
         request: GetSavedRequest,
     ) -> Result<PagingObject<SavedShow>, Box<dyn Error>> {
         self.get_saved(ObjectType::Shows, request).await
     }
 
     pub async fn get_saved_tracks(
-        &mut self,
+        &self,
         request: GetSavedRequest,
     ) -> Result<PagingObject<SavedTrack>, Box<dyn Error>> {
         self.get_saved(ObjectType::Tracks, request).await
     }
 
     async fn get_saved<T: DeserializeOwned + Clone>(
-        &mut self,
+        &self,
         object_type: ObjectType,
         request: GetSavedRequest,
     ) -> Result<PagingObject<T>, Box<dyn Error>> {
-        let url = format!("https://api.spotify.com/v1/me/{}", object_type.to_string());
+        let url = format!("https://api.spotify.com/v1/me/{}", object_type);
 
         let builder = reqwest::Client::new().get(&url);
         let response = self
             .client
+            .clone()
             .set_offset(request.offset)
             .set_limit(request.limit)
             .set_market(request.market)
@@ -135,95 +146,419 @@ impl LibraryClient {
         Ok(response.json().await?)
     }
 
+    async fn get_all_saved<T: DeserializeOwned + Clone>(
+        &self,
+        object_type: ObjectType,
+        market: Option<CountryCode>,
+    ) -> Result<Vec<T>, Box<dyn Error>> {
+        let mut items = Vec::new();
+        let mut offset = 0;
+
+        loop {
+            let request = GetSavedRequest {
+                offset: Some(offset),
+                limit: Some(50),
+                market,
+            };
+
+            let mut page: PagingObject<T> = self.get_saved(object_type, request).await?;
+            let fetched = page.items.len();
+            offset += fetched as u32;
+            items.append(&mut page.items);
+
+            if page.next.is_none() || fetched == 0 {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    pub async fn export_library(
+        &self,
+        request: ExportLibraryRequest,
+    ) -> Result<LibrarySnapshot, Box<dyn Error>> {
+        let mut items = Vec::new();
+
+        let tracks: Vec<SavedTrack> = self
+            .get_all_saved(ObjectType::Tracks, request.market)
+            .await?;
+        items.extend(tracks.into_iter().map(|saved| LibraryItem {
+            object_type: ObjectType::Tracks,
+            id: saved.track.id,
+            name: saved.track.name,
+            added_at: saved.added_at,
+        }));
+
+        let albums: Vec<SavedAlbum> = self
+            .get_all_saved(ObjectType::Albums, request.market)
+            .await?;
+        items.extend(albums.into_iter().map(|saved| LibraryItem {
+            object_type: ObjectType::Albums,
+            id: saved.album.id,
+            name: saved.album.name,
+            added_at: saved.added_at,
+        }));
+
+        let shows: Vec<SavedShow> = self
+            .get_all_saved(ObjectType::Shows, request.market)
+            .await?;
+        items.extend(shows.into_iter().map(|saved| LibraryItem {
+            object_type: ObjectType::Shows,
+            id: saved.show.id,
+            name: saved.show.name,
+            added_at: saved.added_at,
+        }));
+
+        Ok(LibrarySnapshot {
+            version: LibrarySnapshot::VERSION,
+            exported_at: Utc::now(),
+            items,
+        })
+    }
+
+    pub async fn import_library(
+        &self,
+        request: ImportLibraryRequest,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut items = request.snapshot.items;
+        items.sort_by_key(|item| item.added_at);
+
+        for object_type in &[ObjectType::Tracks, ObjectType::Albums, ObjectType::Shows] {
+            let ids = items
+                .iter()
+                .filter(|item| item.object_type == *object_type)
+                .map(|item| item.validated_id())
+                .collect::<Result<Vec<String>, _>>()?;
+
+            // Saves run one chunk at a time, oldest first, so the restored library keeps its order.
+            batch::run_with_concurrency(Endpoint::Library, 1, ids, |_, ids| {
+                self.request_save(*object_type, ids)
+            })
+            .await
+            .into_result()?;
+        }
+
+        Ok(())
+    }
+
     pub async fn remove_saved_albums(
-        &mut self,
-        request: RemoveSavedRequest,
+        &self,
+        request: RemoveSavedRequest<AlbumId>,
     ) -> Result<(), Box<dyn Error>> {
-        self.remove_saved(ObjectType::Albums, request.ids).await
+        self.remove_saved(ObjectType::Albums, id::into_strings(request.ids))
+            .await
     }
 
     pub async fn remove_saved_shows(
-        &mut self,
-        request: RemoveSavedRequest,
+        &self,
+        request: RemoveSavedRequest<ShowId>,
     ) -> Result<(), Box<dyn Error>> {
-        self.remove_saved(ObjectType::Shows, request.ids).await
+        self.remove_saved(ObjectType::Shows, id::into_strings(request.ids))
+            .await
     }
 
     pub async fn remove_saved_tracks(
-        &mut self,
-        request: RemoveSavedRequest,
+        &self,
+        request: RemoveSavedRequest<TrackId>,
     ) -> Result<(), Box<dyn Error>> {
-        self.remove_saved(ObjectType::Tracks, request.ids).await
+        self.remove_saved(ObjectType::Tracks, id::into_strings(request.ids))
+            .await
     }
 
-    fn remove_saved(
-        &mut self,
+    async fn remove_saved(
+        &self,
         object_type: ObjectType,
-        mut ids: Vec<String>,
-    ) -> BoxFuture<'_, Result<(), Box<dyn Error>>> {
-        async move {
-            let url = format!("https://api.spotify.com/v1/me/{}", object_type.to_string());
+        ids: Vec<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        batch::run(Endpoint::Library, ids, |_, ids| {
+            self.request_remove_saved(object_type, ids)
+        })
+        .await
+        .into_result()?;
 
-            if ids.len() > 50 {
-                self.remove_saved(object_type, ids.drain(..50).collect())
-                    .await?;
-                self.remove_saved(object_type, ids.clone()).await?;
+        Ok(())
+    }
 
-                return Ok(());
-            }
+    async fn request_remove_saved(
+        &self,
+        object_type: ObjectType,
+        ids: Vec<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let url = format!("https://api.spotify.com/v1/me/{}", object_type);
+
+        let builder = reqwest::Client::new()
+            .delete(&url)
+            .json(&json!({ "ids": ids }));
+
+        self.client.send(builder).await?.unwrap();
+
+        Ok(())
+    }
+
+    pub async fn save_albums(&self, request: SaveRequest<AlbumId>) -> Result<(), Box<dyn Error>> {
+        self.save(ObjectType::Albums, id::into_strings(request.ids))
+            .await
+    }
+
+    pub async fn save_shows(&self, request: SaveRequest<ShowId>) -> Result<(), Box<dyn Error>> {
+        self.save(ObjectType::Shows, id::into_strings(request.ids))
+            .await
+    }
+
+    pub async fn save_tracks(&self, request: SaveRequest<TrackId>) -> Result<(), Box<dyn Error>> {
+        self.save(ObjectType::Tracks, id::into_strings(request.ids))
+            .await
+    }
 
-            let builder = reqwest::Client::new()
-                .delete(&url)
-                .json(&json!({ "ids": ids }));
+    async fn save(&self, object_type: ObjectType, ids: Vec<String>) -> Result<(), Box<dyn Error>> {
+        batch::run(Endpoint::Library, ids, |_, ids| {
+            self.request_save(object_type, ids)
+        })
+        .await
+        .into_result()?;
+
+        Ok(())
+    }
+
+    async fn request_save(
+        &self,
+        object_type: ObjectType,
+        ids: Vec<String>,
+    ) -> Result<(), Box<dyn Error>> {
+        let url = format!("https://api.spotify.com/v1/me/{}", object_type);
 
-            self.client.send(builder).await?.unwrap();
+        let builder = reqwest::Client::new()
+            .put(&url)
+            .json(&json!({ "ids": ids }));
 
-            Ok(())
+        self.client.send(builder).await?.unwrap();
+
+        Ok(())
+    }
+}
+
+pub const LIBRARY_INDEX_TTL: Duration = Duration::from_secs(600);
+
+#[derive(Clone, Debug)]
+pub struct LibraryIndex {
+    client: LibraryClient,
+    ttl: Duration,
+    sets: Arc<Mutex<HashMap<ObjectType, IndexSet>>>,
+}
+
+#[derive(Debug)]
+struct IndexSet {
+    membership: HashMap<String, bool>,
+    complete: bool,
+    loaded_at: Instant,
+}
+
+impl IndexSet {
+    fn new() -> Self {
+        IndexSet {
+            membership: HashMap::new(),
+            complete: false,
+            loaded_at: Instant::now(),
         }
-        .boxed()
     }
 
-    pub async fn save_albums(&mut self, request: SaveRequest) -> Result<(), Box<dyn Error>> {
-        self.save(ObjectType::Albums, request.ids).await
+    fn get(&self, id: &str) -> Option<bool> {
+        match self.membership.get(id) {
+            Some(saved) => Some(*saved),
+            None if self.complete => Some(false),
+            None => None,
+        }
     }
+}
 
-    pub async fn save_shows(&mut self, request: SaveRequest) -> Result<(), Box<dyn Error>> {
-        self.save(ObjectType::Shows, request.ids).await
+impl LibraryIndex {
+    pub fn new(client: LibraryClient) -> Self {
+        LibraryIndex {
+            client,
+            ttl: LIBRARY_INDEX_TTL,
+            sets: Arc::default(),
+        }
     }
 
-    pub async fn save_tracks(&mut self, request: SaveRequest) -> Result<(), Box<dyn Error>> {
-        self.save(ObjectType::Tracks, request.ids).await
+    pub fn set_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
     }
 
-    fn save(
-        &mut self,
+    pub async fn preload(
+        &self,
         object_type: ObjectType,
-        mut ids: Vec<String>,
-    ) -> BoxFuture<'_, Result<(), Box<dyn Error>>> {
-        async move {
-            let url = format!("https://api.spotify.com/v1/me/{}", object_type.to_string());
-
-            if ids.len() > 50 {
-                self.remove_saved(object_type, ids.drain(..50).collect())
-                    .await?;
-                self.remove_saved(object_type, ids.clone()).await?;
-            }
+        market: Option<CountryCode>,
+    ) -> Result<(), Box<dyn Error>> {
+        let ids: Vec<String> = match object_type {
+            ObjectType::Albums => self
+                .client
+                .get_all_saved::<SavedAlbum>(object_type, market)
+                .await?
+                .into_iter()
+                .map(|saved| saved.album.id)
+                .collect(),
+            ObjectType::Shows => self
+                .client
+                .get_all_saved::<SavedShow>(object_type, market)
+                .await?
+                .into_iter()
+                .map(|saved| saved.show.id)
+                .collect(),
+            ObjectType::Tracks => self
+                .client
+                .get_all_saved::<SavedTrack>(object_type, market)
+                .await?
+                .into_iter()
+                .map(|saved| saved.track.id)
+                .collect(),
+        };
+
+        let mut set = IndexSet::new();
+        set.membership = ids.into_iter().map(|id| (id, true)).collect();
+        set.complete = true;
+        self.sets.lock().unwrap().insert(object_type, set);
+
+        Ok(())
+    }
+
+    pub fn contains(&self, object_type: ObjectType, id: &str) -> Option<bool> {
+        let mut sets = self.sets.lock().unwrap();
+
+        self.fresh_set(&mut sets, object_type).get(id)
+    }
+
+    pub fn invalidate(&self) {
+        self.sets.lock().unwrap().clear();
+    }
+
+    pub async fn is_saved_albums(&self, ids: &[AlbumId]) -> Result<Vec<bool>, Box<dyn Error>> {
+        self.is_saved(ObjectType::Albums, id::into_strings(ids.to_vec()))
+            .await
+    }
+
+    pub async fn is_saved_shows(&self, ids: &[ShowId]) -> Result<Vec<bool>, Box<dyn Error>> {
+        self.is_saved(ObjectType::Shows, id::into_strings(ids.to_vec()))
+            .await
+    }
+
+    pub async fn is_saved_tracks(&self, ids: &[TrackId]) -> Result<Vec<bool>, Box<dyn Error>> {
+        self.is_saved(ObjectType::Tracks, id::into_strings(ids.to_vec()))
+            .await
+    }
+
+    async fn is_saved(
+        &self,
+        object_type: ObjectType,
+        ids: Vec<String>,
+    ) -> Result<Vec<bool>, Box<dyn Error>> {
+        let missing: Vec<String> = {
+            let mut sets = self.sets.lock().unwrap();
+            let set = self.fresh_set(&mut sets, object_type);
+
+            let mut seen = HashSet::new();
+            ids.iter()
+                .filter(|id| set.get(id).is_none() && seen.insert(id.as_str()))
+                .cloned()
+                .collect()
+        };
+
+        if !missing.is_empty() {
+            let saved = self.client.is_saved(object_type, missing.clone()).await?;
+            self.update(object_type, missing.into_iter().zip(saved));
+        }
+
+        let mut sets = self.sets.lock().unwrap();
+        let set = self.fresh_set(&mut sets, object_type);
+
+        Ok(ids
+            .iter()
+            .map(|id| set.get(id).unwrap_or_default())
+            .collect())
+    }
+
+    pub async fn save_albums(&self, request: SaveRequest<AlbumId>) -> Result<(), Box<dyn Error>> {
+        self.save(ObjectType::Albums, id::into_strings(request.ids), true)
+            .await
+    }
+
+    pub async fn save_shows(&self, request: SaveRequest<ShowId>) -> Result<(), Box<dyn Error>> {
+        self.save(ObjectType::Shows, id::into_strings(request.ids), true)
+            .await
+    }
+
+    pub async fn save_tracks(&self, request: SaveRequest<TrackId>) -> Result<(), Box<dyn Error>> {
+        self.save(ObjectType::Tracks, id::into_strings(request.ids), true)
+            .await
+    }
+
+    pub async fn remove_saved_albums(
+        &self,
+        request: RemoveSavedRequest<AlbumId>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(ObjectType::Albums, id::into_strings(request.ids), false)
+            .await
+    }
 
-            let builder = reqwest::Client::new()
-                .put(&url)
-                .json(&json!({ "ids": ids }));
+    pub async fn remove_saved_shows(
+        &self,
+        request: RemoveSavedRequest<ShowId>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(ObjectType::Shows, id::into_strings(request.ids), false)
+            .await
+    }
 
-            self.client.send(builder).await?.unwrap();
+    pub async fn remove_saved_tracks(
+        &self,
+        request: RemoveSavedRequest<TrackId>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.save(ObjectType::Tracks, id::into_strings(request.ids), false)
+            .await
+    }
 
-            Ok(())
+    async fn save(
+        &self,
+        object_type: ObjectType,
+        ids: Vec<String>,
+        saved: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        if saved {
+            self.client.save(object_type, ids.clone()).await?;
+        } else {
+            self.client.remove_saved(object_type, ids.clone()).await?;
         }
-        .boxed()
+
+        self.update(object_type, ids.into_iter().map(|id| (id, saved)));
+
+        Ok(())
+    }
+
+    fn update<I: IntoIterator<Item = (String, bool)>>(&self, object_type: ObjectType, entries: I) {
+        let mut sets = self.sets.lock().unwrap();
+
+        self.fresh_set(&mut sets, object_type)
+            .membership
+            .extend(entries);
+    }
+
+    fn fresh_set<'a>(
+        &self,
+        sets: &'a mut HashMap<ObjectType, IndexSet>,
+        object_type: ObjectType,
+    ) -> &'a mut IndexSet {
+        let set = sets.entry(object_type).or_insert_with(IndexSet::new);
+        if set.loaded_at.elapsed() >= self.ttl {
+            *set = IndexSet::new();
+        }
+
+        set
     }
 }
 
 #[derive(Clone, Debug, Default)]
-pub struct CheckSavedRequest {
-    pub ids: Vec<String>,
+pub struct CheckSavedRequest<T> {
+    pub ids: Vec<T>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -234,19 +569,226 @@ pub struct GetSavedRequest {
 }
 
 #[derive(Clone, Debug, Default)]
-pub struct RemoveSavedRequest {
-    pub ids: Vec<String>,
+pub struct RemoveSavedRequest<T> {
+    pub ids: Vec<T>,
 }
 
 #[derive(Clone, Debug, Default)]
-pub struct SaveRequest {
-    pub ids: Vec<String>,
+pub struct SaveRequest<T> {
+    pub ids: Vec<T>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ExportLibraryRequest {
+    pub market: Option<CountryCode>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ImportLibraryRequest {
+    pub snapshot: LibrarySnapshot,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LibrarySnapshot {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub items: Vec<LibraryItem>,
+}
+
+impl LibrarySnapshot {
+    pub const VERSION: u32 = 1;
+
+    const CSV_HEADER: &'static str = "type,id,name,added_at";
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        object::from_versioned_json(json, "library snapshot", LibrarySnapshot::VERSION)
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(LibrarySnapshot::CSV_HEADER);
+        csv.push('\n');
+
+        for item in &self.items {
+            let added_at = item
+                .added_at
+                .map(|added_at| added_at.to_rfc3339())
+                .unwrap_or_default();
+            let fields = [
+                item.object_type.to_string(),
+                item.id.clone(),
+                item.name.clone(),
+                added_at,
+            ];
+
+            let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    pub fn from_csv(csv: &str) -> Result<Self, Box<dyn Error>> {
+        let mut rows = parse_csv(csv)?.into_iter();
+
+        match rows.next() {
+            Some(header) if header.join(",") == LibrarySnapshot::CSV_HEADER => {}
+            _ => return Err("missing library csv header".into()),
+        }
+
+        let mut items = Vec::new();
+        for (line, row) in rows.enumerate() {
+            if row.len() != 4 {
+                return Err(
+                    format!("expected 4 fields on row {}, found {}", line + 1, row.len()).into(),
+                );
+            }
+
+            let object_type = match row[0].as_str() {
+                "tracks" => ObjectType::Tracks,
+                "albums" => ObjectType::Albums,
+                "shows" => ObjectType::Shows,
+                other => return Err(format!("unknown library item type {}", other).into()),
+            };
+            let added_at = if row[3].is_empty() {
+                None
+            } else {
+                Some(DateTime::parse_from_rfc3339(&row[3])?.with_timezone(&Utc))
+            };
+
+            items.push(LibraryItem {
+                object_type,
+                id: row[1].clone(),
+                name: row[2].clone(),
+                added_at,
+            });
+        }
+
+        Ok(LibrarySnapshot {
+            version: LibrarySnapshot::VERSION,
+            exported_at: Utc::now(),
+            items,
+        })
+    }
+
+    pub fn diff(&self, other: &LibrarySnapshot) -> LibraryDiff {
+        let keys = |snapshot: &LibrarySnapshot| -> HashSet<(ObjectType, String)> {
+            snapshot
+                .items
+                .iter()
+                .map(|item| (item.object_type, item.id.clone()))
+                .collect()
+        };
+        let before = keys(self);
+        let after = keys(other);
+
+        LibraryDiff {
+            added: other
+                .items
+                .iter()
+                .filter(|item| !before.contains(&(item.object_type, item.id.clone())))
+                .cloned()
+                .collect(),
+            removed: self
+                .items
+                .iter()
+                .filter(|item| !after.contains(&(item.object_type, item.id.clone())))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct LibraryItem {
+    #[serde(rename = "type")]
+    pub object_type: ObjectType,
+    pub id: String,
+    pub name: String,
+    pub added_at: Option<DateTime<Utc>>,
+}
+
+impl LibraryItem {
+    fn validated_id(&self) -> Result<String, Box<dyn Error>> {
+        let id = match self.object_type {
+            ObjectType::Albums => self.id.parse::<AlbumId>()?.into(),
+            ObjectType::Shows => self.id.parse::<ShowId>()?.into(),
+            ObjectType::Tracks => self.id.parse::<TrackId>()?.into(),
+        };
+
+        Ok(id)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LibraryDiff {
+    pub added: Vec<LibraryItem>,
+    pub removed: Vec<LibraryItem>,
+}
+
+impl LibraryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn parse_csv(csv: &str) -> Result<Vec<Vec<String>>, Box<dyn Error>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err("unterminated quoted csv field".into());
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows)
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(
+    Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum ObjectType {
     Albums,
     Shows,
+    #[default]
     Tracks,
 }
 
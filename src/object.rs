@@ -2,20 +2,34 @@ use std::error::Error;
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::RequestClient;
+use crate::{error::ValidationError, RequestClient};
 
 async fn get_paging_object<T: DeserializeOwned>(
     url: &str,
     access_token: &str,
     refresh_token: &str,
 ) -> Result<T, Box<dyn Error>> {
-    let mut client = RequestClient::new(access_token, refresh_token);
+    let client = RequestClient::new(access_token, refresh_token);
     let request = reqwest::Client::new().get(url);
     let response = client.send(request).await?.unwrap();
 
     Ok(response.json().await?)
 }
 
+pub(crate) fn from_versioned_json<T: DeserializeOwned>(
+    json: &str,
+    document: &'static str,
+    supported: u32,
+) -> Result<T, Box<dyn Error>> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    let version = value["version"].as_u64().unwrap_or(0);
+    if version == 0 || version > u64::from(supported) {
+        return Err(ValidationError::UnsupportedVersion { document, version }.into());
+    }
+
+    Ok(serde_json::from_value(value)?)
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct PagingObject<T> {
     pub href: String,
@@ -92,7 +106,8 @@ impl<T: DeserializeOwned + Clone> PagingObject<T> {
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Cursor {
-    after: String,
+    pub after: Option<String>,
+    pub before: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -101,7 +116,7 @@ pub struct CursorPagingObject<T> {
     pub items: Vec<T>,
     pub limit: u32,
     pub next: Option<String>,
-    pub cursors: Cursor,
+    pub cursors: Option<Cursor>,
     pub total: Option<u32>,
 }
 
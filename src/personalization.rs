@@ -1,8 +1,19 @@
-use std::error::Error;
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+};
 
-use serde::de::DeserializeOwned;
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use crate::{artist::Artist, object::PagingObject, track::Track, RequestClient};
+use crate::{
+    artist::Artist,
+    object::{self, PagingObject},
+    track::Track,
+    RequestClient,
+};
+
+pub const TOP_PAGE_LIMIT: u32 = 50;
 
 #[derive(Clone, Debug, Default)]
 pub struct PersonalizationClient {
@@ -17,28 +28,91 @@ impl PersonalizationClient {
     }
 
     pub async fn get_top_artists(
-        &mut self,
+        &self,
         request: GetTopRequest,
     ) -> Result<PagingObject<Artist>, Box<dyn Error>> {
         self.get_top(ObjectType::Artists, request).await
     }
 
     pub async fn get_top_tracks(
-        &mut self,
+        &self,
         request: GetTopRequest,
     ) -> Result<PagingObject<Track>, Box<dyn Error>> {
         self.get_top(ObjectType::Tracks, request).await
     }
 
+    pub async fn get_all_top_artists(
+        &self,
+        time_range: TimeRange,
+    ) -> Result<Vec<Artist>, Box<dyn Error>> {
+        self.get_all_top(ObjectType::Artists, time_range).await
+    }
+
+    pub async fn get_all_top_tracks(
+        &self,
+        time_range: TimeRange,
+    ) -> Result<Vec<Track>, Box<dyn Error>> {
+        self.get_all_top(ObjectType::Tracks, time_range).await
+    }
+
+    pub async fn get_top_report(&self) -> Result<TopReport, Box<dyn Error>> {
+        let mut artists = BTreeMap::new();
+        let mut tracks = BTreeMap::new();
+
+        for &time_range in TimeRange::ALL.iter() {
+            let items = self.get_all_top_artists(time_range).await?;
+            artists.insert(
+                time_range,
+                RankedItem::rank(items.into_iter().map(|a| (a.id, a.name))),
+            );
+
+            let items = self.get_all_top_tracks(time_range).await?;
+            tracks.insert(
+                time_range,
+                RankedItem::rank(items.into_iter().map(|t| (t.id, t.name))),
+            );
+        }
+
+        Ok(TopReport {
+            version: TopReport::VERSION,
+            taken_at: Utc::now(),
+            artists,
+            tracks,
+        })
+    }
+
+    async fn get_all_top<T: DeserializeOwned + Clone>(
+        &self,
+        object_type: ObjectType,
+        time_range: TimeRange,
+    ) -> Result<Vec<T>, Box<dyn Error>> {
+        let mut items = Vec::new();
+
+        loop {
+            let request = GetTopRequest {
+                offset: Some(items.len() as u32),
+                limit: Some(TOP_PAGE_LIMIT),
+                time_range: Some(time_range),
+            };
+
+            let mut page: PagingObject<T> = self.get_top(object_type, request).await?;
+            let fetched = page.items.len();
+            items.append(&mut page.items);
+
+            if page.next.is_none() || fetched == 0 {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
     async fn get_top<T: DeserializeOwned + Clone>(
-        &mut self,
+        &self,
         object_type: ObjectType,
         request: GetTopRequest,
     ) -> Result<PagingObject<T>, Box<dyn Error>> {
-        let url = format!(
-            "https://api.spotify.com/v1/me/top/{}",
-            object_type.to_string()
-        );
+        let url = format!("https://api.spotify.com/v1/me/top/{}", object_type);
 
         let query = if let Some(time_range) = request.time_range {
             vec![("time_range", time_range.to_string())]
@@ -49,6 +123,7 @@ impl PersonalizationClient {
         let builder = reqwest::Client::new().get(&url).query(&query);
         let response = self
             .client
+            .clone()
             .set_offset(request.offset)
             .set_limit(request.limit)
             .send(builder)
@@ -66,13 +141,25 @@ pub struct GetTopRequest {
     pub time_range: Option<TimeRange>,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(
+    Copy, Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
 pub enum TimeRange {
     LongTerm,
+    #[default]
     MediumTerm,
     ShortTerm,
 }
 
+impl TimeRange {
+    pub const ALL: [TimeRange; 3] = [
+        TimeRange::ShortTerm,
+        TimeRange::MediumTerm,
+        TimeRange::LongTerm,
+    ];
+}
+
 impl std::fmt::Display for TimeRange {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -83,13 +170,7 @@ impl std::fmt::Display for TimeRange {
     }
 }
 
-impl Default for TimeRange {
-    fn default() -> Self {
-        TimeRange::MediumTerm
-    }
-}
-
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ObjectType {
     Artists,
     Tracks,
@@ -103,3 +184,137 @@ impl std::fmt::Display for ObjectType {
         }
     }
 }
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct RankedItem {
+    pub id: String,
+    pub name: String,
+    pub rank: u32,
+}
+
+impl RankedItem {
+    pub fn rank<I: IntoIterator<Item = (String, String)>>(items: I) -> Vec<RankedItem> {
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(index, (id, name))| RankedItem {
+                id,
+                name,
+                rank: index as u32 + 1,
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RankMove {
+    pub id: String,
+    pub name: String,
+    pub from: u32,
+    pub to: u32,
+}
+
+impl RankMove {
+    pub fn delta(&self) -> i64 {
+        i64::from(self.from) - i64::from(self.to)
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RankComparison {
+    pub moves: Vec<RankMove>,
+    pub new_entries: Vec<RankedItem>,
+    pub dropped: Vec<RankedItem>,
+}
+
+impl RankComparison {
+    pub fn new(from: &[RankedItem], to: &[RankedItem]) -> Self {
+        let previous: HashMap<&str, &RankedItem> =
+            from.iter().map(|item| (item.id.as_str(), item)).collect();
+        let current: HashMap<&str, &RankedItem> =
+            to.iter().map(|item| (item.id.as_str(), item)).collect();
+
+        let mut comparison = RankComparison::default();
+        for item in to {
+            match previous.get(item.id.as_str()) {
+                Some(before) => comparison.moves.push(RankMove {
+                    id: item.id.clone(),
+                    name: item.name.clone(),
+                    from: before.rank,
+                    to: item.rank,
+                }),
+                None => comparison.new_entries.push(item.clone()),
+            }
+        }
+
+        comparison.dropped = from
+            .iter()
+            .filter(|item| !current.contains_key(item.id.as_str()))
+            .cloned()
+            .collect();
+
+        comparison
+    }
+
+    pub fn climbers(&self) -> Vec<&RankMove> {
+        let mut climbers: Vec<&RankMove> = self.moves.iter().filter(|m| m.delta() > 0).collect();
+        climbers.sort_by_key(|m| std::cmp::Reverse(m.delta()));
+        climbers
+    }
+
+    pub fn fallers(&self) -> Vec<&RankMove> {
+        let mut fallers: Vec<&RankMove> = self.moves.iter().filter(|m| m.delta() < 0).collect();
+        fallers.sort_by_key(|m| m.delta());
+        fallers
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TopReport {
+    pub version: u32,
+    pub taken_at: DateTime<Utc>,
+    pub artists: BTreeMap<TimeRange, Vec<RankedItem>>,
+    pub tracks: BTreeMap<TimeRange, Vec<RankedItem>>,
+}
+
+impl TopReport {
+    pub const VERSION: u32 = 1;
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        object::from_versioned_json(json, "top report", TopReport::VERSION)
+    }
+
+    pub fn items(&self, object_type: ObjectType, time_range: TimeRange) -> &[RankedItem] {
+        let ranges = match object_type {
+            ObjectType::Artists => &self.artists,
+            ObjectType::Tracks => &self.tracks,
+        };
+
+        ranges.get(&time_range).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn compare_ranges(
+        &self,
+        object_type: ObjectType,
+        from: TimeRange,
+        to: TimeRange,
+    ) -> RankComparison {
+        RankComparison::new(self.items(object_type, from), self.items(object_type, to))
+    }
+
+    pub fn compare(
+        &self,
+        previous: &TopReport,
+        object_type: ObjectType,
+        time_range: TimeRange,
+    ) -> RankComparison {
+        RankComparison::new(
+            previous.items(object_type, time_range),
+            self.items(object_type, time_range),
+        )
+    }
+}
@@ -1,35 +1,52 @@
-use std::error::Error;
+use std::{
+    convert::TryFrom,
+    error::Error,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream, StreamExt};
 use isocountry::CountryCode;
 use reqwest::{
     header::{HeaderMap, CONTENT_LENGTH, CONTENT_TYPE},
     Method, StatusCode,
 };
-use serde::{de::DeserializeOwned, Deserialize};
+use serde::{
+    de::{self, DeserializeOwned, Deserializer},
+    Deserialize, Serialize,
+};
 use serde_json::json;
 
 use crate::{
+    episode::Episode,
+    error::{ApiError, PlayerError, ValidationError},
+    id::{AlbumId, ArtistId, Id, PlayableId, PlaylistId, ShowId},
     object::CursorPagingObject,
     track::{SimpleTrack, Track},
     RequestClient,
 };
 
+pub const DEVICE_CACHE_TTL: Duration = Duration::from_secs(5);
+
 #[derive(Clone, Debug, Default)]
 pub struct PlayerClient {
     client: RequestClient,
+    devices: Arc<Mutex<DeviceCache>>,
 }
 
 impl PlayerClient {
     pub fn new(access_token: &str, refresh_token: &str) -> Self {
         PlayerClient {
             client: RequestClient::new(access_token, refresh_token),
+            ..Default::default()
         }
     }
 
-    pub async fn add_item(&mut self, request: AddItemRequest) -> Result<(), Box<dyn Error>> {
-        let mut query = vec![("uri", request.uri)];
-        if let Some(device_id) = request.device_id {
+    pub async fn add_item(&self, request: AddItemRequest) -> Result<(), Box<dyn Error>> {
+        let mut query = vec![("uri", request.uri.uri())];
+        if let Some(device_id) = self.device_id(request.device).await? {
             query.push(("device_id", device_id));
         }
 
@@ -40,20 +57,87 @@ impl PlayerClient {
             .post("https://api.spotify.com/v1/me/player/queue")
             .headers(headers)
             .query(&query);
-        self.client.send(builder).await?.unwrap();
+        self.client
+            .send(builder)
+            .await
+            .map_err(PlayerError::from_api_error)?
+            .unwrap();
 
         Ok(())
     }
 
-    pub async fn get_devices(&mut self) -> Result<GetDevicesResponse, Box<dyn Error>> {
+    pub async fn get_devices(&self) -> Result<GetDevicesResponse, Box<dyn Error>> {
         let builder = reqwest::Client::new().get("https://api.spotify.com/v1/me/player/devices");
-        let response = self.client.send(builder).await?.unwrap();
+        let response = self
+            .client
+            .send(builder)
+            .await
+            .map_err(PlayerError::from_api_error)?
+            .unwrap();
+        let response: GetDevicesResponse = response.json().await?;
+
+        self.devices.lock().unwrap().update(&response.devices);
+
+        Ok(response)
+    }
+
+    pub async fn resolve_device(
+        &self,
+        selector: &DeviceSelector,
+    ) -> Result<String, Box<dyn Error>> {
+        if let DeviceSelector::Id(id) = selector {
+            return Ok(id.clone());
+        }
+
+        let cached = {
+            let cache = self.devices.lock().unwrap();
+            if cache.is_fresh() {
+                cache.select(selector)
+            } else {
+                None
+            }
+        };
+
+        if let Some(id) = cached {
+            return Ok(id);
+        }
+
+        self.get_devices().await?;
+
+        let id = self.devices.lock().unwrap().select(selector);
+        match (id, selector) {
+            (Some(id), _) => Ok(id),
+            (None, DeviceSelector::Active) | (None, DeviceSelector::MostRecentlyActive) => {
+                Err(PlayerError::NoActiveDevice.into())
+            }
+            (None, selector) => Err(PlayerError::DeviceNotFound(selector.clone()).into()),
+        }
+    }
+
+    async fn device_id(
+        &self,
+        device: Option<DeviceSelector>,
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        match device {
+            Some(selector) => Ok(Some(self.resolve_device(&selector).await?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn get_queue(&self) -> Result<Queue, Box<dyn Error>> {
+        let builder = reqwest::Client::new().get("https://api.spotify.com/v1/me/player/queue");
+        let response = self
+            .client
+            .send(builder)
+            .await
+            .map_err(PlayerError::from_api_error)?
+            .unwrap();
 
         Ok(response.json().await?)
     }
 
     pub async fn get_current_playback(
-        &mut self,
+        &self,
         request: GetCurrentlyRequest,
     ) -> Result<Option<CurrentlyPlayingContext>, Box<dyn Error>> {
         self.get_current("https://api.spotify.com/v1/me/player", request)
@@ -61,7 +145,7 @@ impl PlayerClient {
     }
 
     pub async fn get_currently_playing_track(
-        &mut self,
+        &self,
         request: GetCurrentlyRequest,
     ) -> Result<Option<CurrentlyPlayingObject>, Box<dyn Error>> {
         self.get_current(
@@ -72,31 +156,22 @@ impl PlayerClient {
     }
 
     async fn get_current<T>(
-        &mut self,
+        &self,
         url: &str,
         request: GetCurrentlyRequest,
     ) -> Result<Option<T>, Box<dyn Error>>
     where
         T: DeserializeOwned,
     {
-        let query = if let Some(additional_types) = request.additional_types {
-            let s: Vec<String> = additional_types
-                .into_iter()
-                .map(|v| v.to_string())
-                .collect();
-
-            vec![("additional_types", s)]
-        } else {
-            Vec::new()
-        };
-
-        let builder = reqwest::Client::new().get(url).query(&query);
+        let builder = reqwest::Client::new().get(url).query(&request.query());
 
         let response = self
             .client
+            .clone()
             .set_market(request.market)
             .send(builder)
-            .await?
+            .await
+            .map_err(PlayerError::from_api_error)?
             .unwrap();
 
         match response.status() {
@@ -106,17 +181,17 @@ impl PlayerClient {
     }
 
     pub async fn get_recently_played_tracks(
-        &mut self,
+        &self,
         request: GetRecentlyPlayedTracksRequest,
     ) -> Result<CursorPagingObject<PlayHistory>, Box<dyn Error>> {
         let mut query = Vec::new();
 
         if let Some(after) = request.after {
-            query.push(("after", after));
+            query.push(("after", after.timestamp_millis()));
         }
 
         if let Some(before) = request.before {
-            query.push(("before", before));
+            query.push(("before", before.timestamp_millis()));
         }
 
         let builder = reqwest::Client::new()
@@ -125,68 +200,69 @@ impl PlayerClient {
 
         let response = self
             .client
+            .clone()
             .set_limit(request.limit)
             .send(builder)
-            .await?
+            .await
+            .map_err(PlayerError::from_api_error)?
             .unwrap();
 
         Ok(response.json().await?)
     }
 
-    pub async fn pause(&mut self, request: PauseRequest) -> Result<(), Box<dyn Error>> {
-        self.action(ActionType::Pause, None, request.device_id)
-            .await
+    pub async fn pause(&self, request: PauseRequest) -> Result<(), Box<dyn Error>> {
+        self.action(ActionType::Pause, None, request.device).await
     }
 
-    pub async fn seek_to_position(&mut self, request: SeekRequest) -> Result<(), Box<dyn Error>> {
+    pub async fn seek_to_position(&self, request: SeekRequest) -> Result<(), Box<dyn Error>> {
         let query = vec![("position_ms", request.position_ms.to_string())];
 
-        self.action(ActionType::Seek, Some(query), request.device_id)
+        self.action(ActionType::Seek, Some(query), request.device)
             .await
     }
 
     pub async fn set_repeat_mode(
-        &mut self,
+        &self,
         request: SetRepeatModeRequest,
     ) -> Result<(), Box<dyn Error>> {
         let query = vec![("state", request.state.to_string())];
 
-        self.action(ActionType::SetRepeatMode, Some(query), request.device_id)
+        self.action(ActionType::SetRepeatMode, Some(query), request.device)
             .await
     }
 
-    pub async fn set_volume(&mut self, request: SetVolumeRequest) -> Result<(), Box<dyn Error>> {
-        let query = vec![("volume_percent", request.volume_percent.to_string())];
+    pub async fn set_volume(&self, request: SetVolumeRequest) -> Result<(), Box<dyn Error>> {
+        let query = vec![("volume_percent", request.volume_percent.get().to_string())];
 
-        self.action(ActionType::SetVolume, Some(query), request.device_id)
+        self.action(ActionType::SetVolume, Some(query), request.device)
             .await
     }
 
-    pub async fn skip_next(&mut self, request: SkipRequest) -> Result<(), Box<dyn Error>> {
-        self.action(ActionType::SkipNext, None, request.device_id)
+    pub async fn skip_next(&self, request: SkipRequest) -> Result<(), Box<dyn Error>> {
+        self.action(ActionType::SkipNext, None, request.device)
             .await
     }
 
-    pub async fn skip_previous(&mut self, request: SkipRequest) -> Result<(), Box<dyn Error>> {
-        self.action(ActionType::SkipPrevious, None, request.device_id)
+    pub async fn skip_previous(&self, request: SkipRequest) -> Result<(), Box<dyn Error>> {
+        self.action(ActionType::SkipPrevious, None, request.device)
             .await
     }
 
     pub async fn toggle_shuffle(
-        &mut self,
+        &self,
         request: ToggleShuffleRequest,
     ) -> Result<(), Box<dyn Error>> {
         let query = vec![("state", request.state.to_string())];
 
-        self.action(ActionType::ToggleShuffle, Some(query), request.device_id)
+        self.action(ActionType::ToggleShuffle, Some(query), request.device)
             .await
     }
 
     async fn action(
-        &mut self,
+        &self,
         action_type: ActionType,
         query: Option<Vec<(&str, String)>>,
-        device_id: Option<String>,
+        device: Option<DeviceSelector>,
     ) -> Result<(), Box<dyn Error>> {
         let url = format!(
             "https://api.spotify.com/v1/me/player/{}",
@@ -194,7 +270,7 @@ impl PlayerClient {
         );
 
         let mut query = query.unwrap_or_default();
-        if let Some(device_id) = device_id {
+        if let Some(device_id) = self.device_id(device).await? {
             query.push(("device_id", device_id));
         }
 
@@ -206,12 +282,16 @@ impl PlayerClient {
             .headers(headers)
             .query(&query);
 
-        self.client.send(builder).await?.unwrap();
+        self.client
+            .send(builder)
+            .await
+            .map_err(PlayerError::from_api_error)?
+            .unwrap();
 
         Ok(())
     }
 
-    pub async fn start(&mut self, request: Option<StartRequest>) -> Result<(), Box<dyn Error>> {
+    pub async fn start(&self, request: Option<StartRequest>) -> Result<(), Box<dyn Error>> {
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
         headers.insert(CONTENT_LENGTH, "0".parse().unwrap());
@@ -221,46 +301,34 @@ impl PlayerClient {
             .headers(headers);
 
         if let Some(request) = request {
-            if let Some(device_id) = request.device_id {
+            let (json, device) = request.into_json();
+            if let Some(device_id) = self.device_id(device).await? {
                 builder = builder.query(&[("device_id", device_id)]);
             }
 
-            let mut json = if let Some(context_uri) = request.context_uri {
-                let mut map = serde_json::Map::new();
-                map.insert("context_uri".to_string(), json!(context_uri));
-
-                map
-            } else if let Some(uris) = request.uris {
-                let mut map = serde_json::Map::new();
-                map.insert("uris".to_string(), json!(uris));
-
-                map
-            } else {
-                serde_json::Map::new()
-            };
-
-            if let Some(offset) = request.offset {
-                json.insert("offset".to_string(), json!(offset));
-            }
-
-            if let Some(position_ms) = request.position_ms {
-                json.insert("position_ms".to_string(), json!(position_ms));
-            }
-
             builder = builder.json(&json);
         }
 
-        self.client.send(builder).await?.unwrap();
+        self.client
+            .send(builder)
+            .await
+            .map_err(PlayerError::from_api_error)?
+            .unwrap();
 
         Ok(())
     }
 
     pub async fn transfer_playlback(
-        &mut self,
+        &self,
         request: TransferPlaybackRequest,
     ) -> Result<(), Box<dyn Error>> {
+        if request.device == DeviceSelector::Active {
+            return Err(ValidationError::TransferToActiveDevice.into());
+        }
+
         let mut json = serde_json::Map::new();
-        json.insert("device_ids".to_string(), json!([request.device_id]));
+        let device_id = self.resolve_device(&request.device).await?;
+        json.insert("device_ids".to_string(), json!([device_id]));
 
         if let Some(play) = request.play {
             json.insert("play".to_string(), json!(play));
@@ -270,16 +338,113 @@ impl PlayerClient {
             .put("https://api.spotify.com/v1/me/player")
             .json(&json);
 
-        self.client.send(builder).await?.unwrap();
+        self.client
+            .send(builder)
+            .await
+            .map_err(PlayerError::from_api_error)?
+            .unwrap();
 
         Ok(())
     }
+
+    pub fn watch_playback(
+        &self,
+        request: WatchPlaybackRequest,
+    ) -> impl Stream<Item = Result<PlaybackEvent, Box<dyn Error>>> + '_ {
+        let state = WatchState {
+            previous: None,
+            interval: request.interval,
+            polled: false,
+        };
+
+        stream::unfold(state, move |mut state| {
+            let request = request.clone();
+
+            async move {
+                if state.polled {
+                    tokio::time::delay_for(state.interval).await;
+                }
+                state.polled = true;
+
+                let current = self
+                    .get_current_playback(GetCurrentlyRequest {
+                        market: request.market,
+                        additional_types: request.additional_types.clone(),
+                    })
+                    .await;
+
+                let results = match current {
+                    Ok(current) => {
+                        let received_at = Instant::now();
+                        let kinds = match &state.previous {
+                            Some((previous, previous_at)) => PlaybackEventKind::between(
+                                previous.as_ref(),
+                                current.as_ref(),
+                                received_at.duration_since(*previous_at),
+                            ),
+                            None => PlaybackEventKind::between(
+                                None,
+                                current.as_ref(),
+                                Duration::default(),
+                            ),
+                        };
+
+                        state.interval = if kinds.is_empty() {
+                            (state.interval * 3 / 2).min(request.max_interval)
+                        } else {
+                            request.interval
+                        };
+
+                        if let Some(remaining) = current
+                            .as_ref()
+                            .filter(|context| context.object.is_playing)
+                            .and_then(|context| context.object.remaining_at(Duration::default()))
+                        {
+                            state.interval = state.interval.min(remaining.max(request.interval));
+                        }
+
+                        let events = kinds
+                            .into_iter()
+                            .map(|kind| {
+                                Ok(PlaybackEvent {
+                                    kind,
+                                    context: current.clone(),
+                                    received_at,
+                                })
+                            })
+                            .collect();
+
+                        state.previous = Some((current, received_at));
+
+                        events
+                    }
+                    Err(error) => match error.downcast_ref::<ApiError>() {
+                        Some(ApiError::RateLimited { retry_after }) => {
+                            state.interval = retry_after
+                                .unwrap_or(state.interval * 2)
+                                .max(state.interval);
+
+                            Vec::new()
+                        }
+                        _ => {
+                            state.interval = (state.interval * 2).min(request.max_interval);
+
+                            vec![Err(error)]
+                        }
+                    },
+                };
+
+                Some((results, state))
+            }
+        })
+        .flat_map(stream::iter)
+    }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct AddItemRequest {
-    pub uri: String,
-    pub device_id: Option<String>,
+    pub uri: PlayableId,
+    pub device: Option<DeviceSelector>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -287,66 +452,552 @@ pub struct GetDevicesResponse {
     pub devices: Vec<Device>,
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum DeviceSelector {
+    Id(String),
+    Name(String),
+    Type(DeviceType),
+    #[default]
+    Active,
+    /// The active device, or else the last device this client saw as active. Only devices
+    /// seen through this client's own device lookups are remembered.
+    MostRecentlyActive,
+}
+
+impl DeviceSelector {
+    pub fn select<'a>(
+        &self,
+        devices: &'a [Device],
+        last_active: Option<&str>,
+    ) -> Option<&'a Device> {
+        let mut devices = devices.iter().filter(|device| device.id.is_some());
+
+        match self {
+            DeviceSelector::Id(id) => devices.find(|device| device.id.as_ref() == Some(id)),
+            DeviceSelector::Name(name) => devices.find(|device| &device.name == name),
+            DeviceSelector::Type(device_type) => {
+                devices.find(|device| &device.device_type == device_type)
+            }
+            DeviceSelector::Active => devices.find(|device| device.is_active),
+            DeviceSelector::MostRecentlyActive => {
+                let devices: Vec<&Device> = devices.collect();
+
+                devices
+                    .iter()
+                    .find(|device| device.is_active)
+                    .or_else(|| {
+                        devices
+                            .iter()
+                            .find(|device| device.id.as_deref() == last_active)
+                    })
+                    .copied()
+            }
+        }
+    }
+}
+
+impl From<String> for DeviceSelector {
+    fn from(id: String) -> Self {
+        DeviceSelector::Id(id)
+    }
+}
+
+impl From<&str> for DeviceSelector {
+    fn from(id: &str) -> Self {
+        DeviceSelector::Id(id.to_string())
+    }
+}
+
+impl fmt::Display for DeviceSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceSelector::Id(id) => write!(f, "id {}", id),
+            DeviceSelector::Name(name) => write!(f, "name {}", name),
+            DeviceSelector::Type(device_type) => write!(f, "type {:?}", device_type),
+            DeviceSelector::Active => write!(f, "active device"),
+            DeviceSelector::MostRecentlyActive => write!(f, "most recently active device"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct DeviceCache {
+    devices: Vec<Device>,
+    fetched_at: Option<Instant>,
+    last_active: Option<String>,
+}
+
+impl DeviceCache {
+    fn update(&mut self, devices: &[Device]) {
+        if let Some(active) = devices.iter().find(|device| device.is_active) {
+            self.last_active = active.id.clone();
+        }
+
+        self.devices = devices.to_vec();
+        self.fetched_at = Some(Instant::now());
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.fetched_at
+            .map(|fetched_at| fetched_at.elapsed() < DEVICE_CACHE_TTL)
+            .unwrap_or(false)
+    }
+
+    fn select(&self, selector: &DeviceSelector) -> Option<String> {
+        selector
+            .select(&self.devices, self.last_active.as_deref())
+            .and_then(|device| device.id.clone())
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Queue {
+    pub currently_playing: Option<PlayableItem>,
+    pub queue: Vec<PlayableItem>,
+}
+
+impl Queue {
+    pub fn contains(&self, uri: &str) -> bool {
+        self.currently_playing
+            .iter()
+            .chain(self.queue.iter())
+            .any(|item| item.uri() == uri)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum PlayableItem {
+    Track(Track),
+    Episode(Episode),
+}
+
+impl PlayableItem {
+    pub fn uri(&self) -> &str {
+        match self {
+            PlayableItem::Track(track) => &track.uri,
+            PlayableItem::Episode(episode) => &episode.uri,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            PlayableItem::Track(track) => &track.name,
+            PlayableItem::Episode(episode) => &episode.name,
+        }
+    }
+
+    pub fn duration_ms(&self) -> u32 {
+        match self {
+            PlayableItem::Track(track) => track.duration_ms,
+            PlayableItem::Episode(episode) => episode.duration_ms,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PlayableItem {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+
+        match value["type"].as_str() {
+            Some("track") => serde_json::from_value(value)
+                .map(PlayableItem::Track)
+                .map_err(de::Error::custom),
+            Some("episode") => serde_json::from_value(value)
+                .map(PlayableItem::Episode)
+                .map_err(de::Error::custom),
+            Some(object_type) => Err(de::Error::unknown_variant(
+                object_type,
+                &["track", "episode"],
+            )),
+            None => Err(de::Error::missing_field("type")),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct GetCurrentlyRequest {
     pub market: Option<CountryCode>,
     pub additional_types: Option<Vec<ObjectType>>,
 }
 
+impl GetCurrentlyRequest {
+    pub fn query(&self) -> Vec<(&'static str, String)> {
+        match &self.additional_types {
+            Some(types) => {
+                let types = types
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                vec![("additional_types", types)]
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct GetRecentlyPlayedTracksRequest {
     pub limit: Option<u32>,
-    pub after: Option<usize>,
-    pub before: Option<usize>,
+    pub after: Option<DateTime<Utc>>,
+    pub before: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct PauseRequest {
-    pub device_id: Option<String>,
+    pub device: Option<DeviceSelector>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct SeekRequest {
-    pub position_ms: usize,
-    pub device_id: Option<String>,
+    position_ms: u32,
+    device: Option<DeviceSelector>,
+}
+
+impl SeekRequest {
+    pub fn new(position_ms: u32, duration_ms: u32) -> Result<Self, ValidationError> {
+        if position_ms > duration_ms {
+            return Err(ValidationError::PositionOutOfRange {
+                position_ms,
+                duration_ms,
+            });
+        }
+
+        Ok(SeekRequest {
+            position_ms,
+            device: None,
+        })
+    }
+
+    pub fn set_device(mut self, value: DeviceSelector) -> Self {
+        self.device = Some(value);
+        self
+    }
+
+    pub fn position_ms(&self) -> u32 {
+        self.position_ms
+    }
+
+    pub fn device(&self) -> Option<&DeviceSelector> {
+        self.device.as_ref()
+    }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct SetRepeatModeRequest {
     pub state: RepeatState,
-    pub device_id: Option<String>,
+    pub device: Option<DeviceSelector>,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct SetVolumeRequest {
-    pub volume_percent: u32,
-    pub device_id: Option<String>,
+    pub volume_percent: VolumePercent,
+    pub device: Option<DeviceSelector>,
+}
+
+impl SetVolumeRequest {
+    pub fn new(volume_percent: u32) -> Result<Self, ValidationError> {
+        Ok(SetVolumeRequest {
+            volume_percent: VolumePercent::new(volume_percent)?,
+            device: None,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct VolumePercent(u8);
+
+impl VolumePercent {
+    pub fn new(volume_percent: u32) -> Result<Self, ValidationError> {
+        if volume_percent > 100 {
+            return Err(ValidationError::VolumeOutOfRange(volume_percent));
+        }
+
+        Ok(VolumePercent(volume_percent as u8))
+    }
+
+    pub fn get(self) -> u32 {
+        u32::from(self.0)
+    }
+}
+
+impl TryFrom<u32> for VolumePercent {
+    type Error = ValidationError;
+
+    fn try_from(volume_percent: u32) -> Result<Self, Self::Error> {
+        VolumePercent::new(volume_percent)
+    }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct SkipRequest {
-    pub device_id: Option<String>,
+    pub device: Option<DeviceSelector>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StartTarget {
+    Context(String),
+    Uris(Vec<PlayableId>),
+}
+
+#[derive(Clone, Debug)]
 pub struct StartRequest {
-    pub context_uri: Option<String>,
-    pub uris: Option<Vec<String>>,
-    pub offset: Option<serde_json::Value>,
-    pub position_ms: Option<usize>,
-    pub device_id: Option<String>,
+    target: StartTarget,
+    offset: Option<Offset>,
+    position_ms: Option<u32>,
+    device: Option<DeviceSelector>,
 }
 
-#[derive(Clone, Debug, Default)]
+impl StartRequest {
+    pub fn context(context_uri: &str) -> Result<Self, ValidationError> {
+        let invalid = || ValidationError::InvalidContextUri(context_uri.to_string());
+
+        // A bare id is ambiguous, so the context type has to come from a uri or url.
+        if !context_uri.contains(':') && !context_uri.contains('/') {
+            return Err(invalid());
+        }
+
+        let uri = context_uri
+            .parse::<AlbumId>()
+            .map(|id| id.uri())
+            .or_else(|_| context_uri.parse::<ArtistId>().map(|id| id.uri()))
+            .or_else(|_| context_uri.parse::<PlaylistId>().map(|id| id.uri()))
+            .or_else(|_| context_uri.parse::<ShowId>().map(|id| id.uri()))
+            .map_err(|_| invalid())?;
+
+        Ok(StartRequest::new(StartTarget::Context(uri)))
+    }
+
+    pub fn uris(uris: Vec<PlayableId>) -> Result<Self, ValidationError> {
+        if uris.is_empty() {
+            return Err(ValidationError::EmptyUris);
+        }
+
+        Ok(StartRequest::new(StartTarget::Uris(uris)))
+    }
+
+    fn new(target: StartTarget) -> Self {
+        StartRequest {
+            target,
+            offset: None,
+            position_ms: None,
+            device: None,
+        }
+    }
+
+    pub fn set_offset(mut self, value: Offset) -> Result<Self, ValidationError> {
+        if let StartTarget::Uris(uris) = &self.target {
+            match &value {
+                Offset::Position(position) if *position as usize >= uris.len() => {
+                    return Err(ValidationError::OffsetOutOfRange {
+                        position: *position,
+                        len: uris.len(),
+                    });
+                }
+                Offset::Uri(uri) if !uris.contains(uri) => {
+                    return Err(ValidationError::OffsetNotInUris(uri.uri()));
+                }
+                _ => {}
+            }
+        }
+
+        self.offset = Some(value);
+        Ok(self)
+    }
+
+    pub fn set_position_ms(mut self, value: u32) -> Self {
+        self.position_ms = Some(value);
+        self
+    }
+
+    pub fn set_device(mut self, value: DeviceSelector) -> Self {
+        self.device = Some(value);
+        self
+    }
+
+    pub fn target(&self) -> &StartTarget {
+        &self.target
+    }
+
+    pub fn offset(&self) -> Option<&Offset> {
+        self.offset.as_ref()
+    }
+
+    pub fn position_ms(&self) -> Option<u32> {
+        self.position_ms
+    }
+
+    pub fn device(&self) -> Option<&DeviceSelector> {
+        self.device.as_ref()
+    }
+
+    fn into_json(
+        self,
+    ) -> (
+        serde_json::Map<String, serde_json::Value>,
+        Option<DeviceSelector>,
+    ) {
+        let mut json = serde_json::Map::new();
+
+        match self.target {
+            StartTarget::Context(context_uri) => {
+                json.insert("context_uri".to_string(), json!(context_uri));
+            }
+            StartTarget::Uris(uris) => {
+                let uris: Vec<String> = uris.iter().map(PlayableId::uri).collect();
+                json.insert("uris".to_string(), json!(uris));
+            }
+        }
+
+        match self.offset {
+            Some(Offset::Position(position)) => {
+                json.insert("offset".to_string(), json!({ "position": position }));
+            }
+            Some(Offset::Uri(uri)) => {
+                json.insert("offset".to_string(), json!({ "uri": uri.uri() }));
+            }
+            None => {}
+        }
+
+        if let Some(position_ms) = self.position_ms {
+            json.insert("position_ms".to_string(), json!(position_ms));
+        }
+
+        (json, self.device)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Offset {
+    Position(u32),
+    Uri(PlayableId),
+}
+
+#[derive(Clone, Debug)]
 pub struct TransferPlaybackRequest {
-    pub device_id: String,
+    pub device: DeviceSelector,
     pub play: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct ToggleShuffleRequest {
     pub state: bool,
-    pub device_id: Option<String>,
+    pub device: Option<DeviceSelector>,
+}
+
+#[derive(Clone, Debug)]
+pub struct WatchPlaybackRequest {
+    pub interval: Duration,
+    pub max_interval: Duration,
+    pub market: Option<CountryCode>,
+    pub additional_types: Option<Vec<ObjectType>>,
+}
+
+impl Default for WatchPlaybackRequest {
+    fn default() -> Self {
+        WatchPlaybackRequest {
+            interval: Duration::from_secs(1),
+            max_interval: Duration::from_secs(15),
+            market: None,
+            additional_types: None,
+        }
+    }
+}
+
+struct WatchState {
+    previous: Option<(Option<CurrentlyPlayingContext>, Instant)>,
+    interval: Duration,
+    polled: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct PlaybackEvent {
+    pub kind: PlaybackEventKind,
+    pub context: Option<CurrentlyPlayingContext>,
+    pub received_at: Instant,
+}
+
+impl PlaybackEvent {
+    pub fn progress_ms(&self) -> Option<u32> {
+        self.context
+            .as_ref()?
+            .object
+            .progress_at(self.received_at.elapsed())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PlaybackEventKind {
+    TrackChanged,
+    Paused,
+    Resumed,
+    Seeked { from_ms: u32, to_ms: u32 },
+    DeviceChanged,
+    VolumeChanged { from: Option<u32>, to: Option<u32> },
+    ShuffleToggled(bool),
+    RepeatChanged(RepeatState),
+    Stopped,
+}
+
+impl PlaybackEventKind {
+    pub const SEEK_TOLERANCE_MS: u32 = 3000;
+
+    pub fn between(
+        previous: Option<&CurrentlyPlayingContext>,
+        current: Option<&CurrentlyPlayingContext>,
+        elapsed: Duration,
+    ) -> Vec<Self> {
+        let (previous, current) = match (previous, current) {
+            (None, None) => return Vec::new(),
+            (Some(_), None) => return vec![PlaybackEventKind::Stopped],
+            (None, Some(_)) => return vec![PlaybackEventKind::TrackChanged],
+            (Some(previous), Some(current)) => (previous, current),
+        };
+
+        let mut events = Vec::new();
+        if previous.object.item_uri() != current.object.item_uri() {
+            events.push(PlaybackEventKind::TrackChanged);
+        } else if previous.object.is_playing == current.object.is_playing {
+            let expected = previous.object.progress_at(elapsed);
+            if let (Some(from_ms), Some(to_ms)) = (expected, current.object.progress_ms) {
+                if (i64::from(from_ms) - i64::from(to_ms)).abs()
+                    > i64::from(PlaybackEventKind::SEEK_TOLERANCE_MS)
+                {
+                    events.push(PlaybackEventKind::Seeked { from_ms, to_ms });
+                }
+            }
+        }
+
+        match (previous.object.is_playing, current.object.is_playing) {
+            (true, false) => events.push(PlaybackEventKind::Paused),
+            (false, true) => events.push(PlaybackEventKind::Resumed),
+            _ => {}
+        }
+
+        if previous.device.id != current.device.id {
+            events.push(PlaybackEventKind::DeviceChanged);
+        } else if previous.device.volume_percent != current.device.volume_percent {
+            events.push(PlaybackEventKind::VolumeChanged {
+                from: previous.device.volume_percent,
+                to: current.device.volume_percent,
+            });
+        }
+
+        if previous.shuffle_state != current.shuffle_state {
+            events.push(PlaybackEventKind::ShuffleToggled(current.shuffle_state));
+        }
+
+        if previous.repeat_state != current.repeat_state {
+            events.push(PlaybackEventKind::RepeatChanged(current.repeat_state));
+        }
+
+        events
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -376,9 +1027,44 @@ impl CurrentlyPlayingObject {
             _ => None,
         }
     }
+
+    pub fn get_item(&self) -> Option<PlayableItem> {
+        serde_json::from_value(self.item.clone()?).ok()
+    }
+
+    pub fn item_uri(&self) -> Option<&str> {
+        self.item.as_ref()?["uri"].as_str()
+    }
+
+    pub fn duration_ms(&self) -> Option<u32> {
+        self.item.as_ref()?["duration_ms"]
+            .as_u64()
+            .map(|duration| duration as u32)
+    }
+
+    pub fn progress_at(&self, elapsed: Duration) -> Option<u32> {
+        let progress = self.progress_ms?;
+        if !self.is_playing {
+            return Some(progress);
+        }
+
+        let progress = progress.saturating_add(elapsed.as_millis() as u32);
+        match self.duration_ms() {
+            Some(duration) => Some(progress.min(duration)),
+            None => Some(progress),
+        }
+    }
+
+    pub fn remaining_at(&self, elapsed: Duration) -> Option<Duration> {
+        let remaining = self
+            .duration_ms()?
+            .checked_sub(self.progress_at(elapsed)?)?;
+
+        Some(Duration::from_millis(u64::from(remaining)))
+    }
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Device {
     pub id: Option<String>,
     pub is_active: bool,
@@ -390,7 +1076,7 @@ pub struct Device {
     pub volume_percent: Option<u32>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub enum DeviceType {
     Computer,
@@ -405,35 +1091,31 @@ pub enum DeviceType {
     CastVideo,
     CastAudio,
     Automobile,
+    #[default]
     Unknown,
 }
 
-impl Default for DeviceType {
-    fn default() -> Self {
-        DeviceType::Unknown
-    }
-}
-
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Context {
-    uri: String,
-    href: Option<String>,
+    pub uri: String,
+    pub href: Option<String>,
     #[serde(rename = "type")]
     pub device_type: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PlayHistory {
     pub track: SimpleTrack,
     pub played_at: DateTime<Utc>,
-    pub context: Context,
+    pub context: Option<Context>,
 }
 
-#[derive(Copy, Clone, Debug, Deserialize)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum RepeatState {
     Track,
     Context,
+    #[default]
     Off,
 }
 
@@ -447,18 +1129,13 @@ impl std::fmt::Display for RepeatState {
     }
 }
 
-impl Default for RepeatState {
-    fn default() -> Self {
-        RepeatState::Off
-    }
-}
-
-#[derive(Copy, Clone, Debug, Deserialize)]
+#[derive(Copy, Clone, Debug, Default, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ObjectType {
     Episode,
     Track,
     Ad,
+    #[default]
     Unknown,
 }
 
@@ -472,12 +1149,6 @@ impl std::fmt::Display for ObjectType {
     }
 }
 
-impl Default for ObjectType {
-    fn default() -> Self {
-        ObjectType::Unknown
-    }
-}
-
 #[derive(Copy, Clone, Debug)]
 pub enum ActionType {
     Pause,
@@ -1,19 +1,26 @@
-use std::error::Error;
+use std::{error::Error, time::Duration};
 
 use chrono::{DateTime, Utc};
-use futures::future::{BoxFuture, FutureExt};
 use isocountry::CountryCode;
 use reqwest::header::{HeaderMap, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use tokio::time::delay_for;
+
+use self::diff::PlaylistDiff;
 
 use crate::{
-    object::{Follower, Image, PagingObject},
+    batch::{self, Endpoint},
+    error::ApiError,
+    id::{IdError, PlayableId, PlaylistId, UserId},
+    object::{self, Follower, Image, PagingObject},
     track::Track,
     user::User,
     RequestClient,
 };
 
+const RATE_LIMIT_DELAY: Duration = Duration::from_secs(1);
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Playlist {
     pub collaborative: bool,
@@ -68,108 +75,110 @@ impl PlaylistClient {
         }
     }
 
-    pub fn add_items(
-        &mut self,
-        mut request: AddItemsRequest,
-    ) -> BoxFuture<'_, Result<Vec<Snapshot>, Box<dyn Error>>> {
-        async move {
-            let url = format!(
-                "https://api.spotify.com/v1/playlists/{}/tracks",
-                request.playlist_id
-            );
-
-            let mut results = Vec::new();
-            if request.uris.len() > 100 {
-                let uris: Vec<String> = request.uris.drain(..100).collect();
-
-                let next_request = AddItemsRequest {
-                    playlist_id: request.playlist_id.clone(),
-                    uris,
-                    position: request.position,
-                };
-
-                results.append(&mut self.add_items(next_request).await?);
-                results.append(&mut self.add_items(request.clone()).await?);
+    pub async fn add_items(
+        &self,
+        request: AddItemsRequest,
+    ) -> Result<Vec<Snapshot>, Box<dyn Error>> {
+        let playlist_id = &request.playlist_id;
+        let position = request.position;
+
+        let uris = request.uris.iter().map(PlayableId::uri).collect();
+        let snapshots =
+            batch::run_with_concurrency(Endpoint::PlaylistItems, 1, uris, |offset, uris| {
+                let position = position.map(|position| position + offset as u32);
+                self.request_add_items(playlist_id, uris, position)
+            })
+            .await
+            .into_result()?;
+
+        Ok(snapshots)
+    }
 
-                return Ok(results);
-            }
+    async fn request_add_items(
+        &self,
+        playlist_id: &PlaylistId,
+        uris: Vec<String>,
+        position: Option<u32>,
+    ) -> Result<Snapshot, Box<dyn Error>> {
+        let url = format!(
+            "https://api.spotify.com/v1/playlists/{}/tracks",
+            playlist_id
+        );
 
-            let mut json = serde_json::Map::new();
-            json.insert("uris".to_string(), json!(request.uris));
+        let mut json = serde_json::Map::new();
+        json.insert("uris".to_string(), json!(uris));
 
-            if let Some(position) = request.position {
-                json.insert("position".to_string(), json!(position));
-            }
+        if let Some(position) = position {
+            json.insert("position".to_string(), json!(position));
+        }
 
-            let mut headers = HeaderMap::new();
-            headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
 
-            let builder = reqwest::Client::new()
-                .post(&url)
-                .headers(headers)
-                .json(&json);
+        let builder = reqwest::Client::new()
+            .post(&url)
+            .headers(headers)
+            .json(&json);
 
-            let response = self.client.send(builder).await?.unwrap();
-            let result = response.json().await?;
-            results.push(result);
+        let response = self.client.send(builder).await?.unwrap();
 
-            Ok(results)
-        }
-        .boxed()
+        Ok(response.json().await?)
     }
 
-    pub async fn change_name(&mut self, request: ChangeNameRequest) -> Result<(), Box<dyn Error>> {
+    pub async fn change_name(&self, request: ChangeNameRequest) -> Result<(), Box<dyn Error>> {
         let request = ChangeDetailRequest {
             playlist_id: request.playlist_id,
             name: Some(request.name),
-            ..Default::default()
+            public: None,
+            collaborative: None,
+            description: None,
         };
 
         self.change_detail(request).await
     }
 
-    pub async fn change_public(
-        &mut self,
-        request: ChangePublicRequest,
-    ) -> Result<(), Box<dyn Error>> {
+    pub async fn change_public(&self, request: ChangePublicRequest) -> Result<(), Box<dyn Error>> {
         let request = ChangeDetailRequest {
             playlist_id: request.playlist_id,
             public: Some(request.public),
-            ..Default::default()
+            name: None,
+            collaborative: None,
+            description: None,
         };
         self.change_detail(request).await
     }
 
     pub async fn change_collaborative(
-        &mut self,
+        &self,
         request: ChangeCollaborativeRequest,
     ) -> Result<(), Box<dyn Error>> {
         let request = ChangeDetailRequest {
             playlist_id: request.playlist_id,
             collaborative: Some(request.collaborative),
-            ..Default::default()
+            name: None,
+            public: None,
+            description: None,
         };
 
         self.change_detail(request).await
     }
 
     pub async fn change_description(
-        &mut self,
+        &self,
         request: ChangeDescriptionRequest,
     ) -> Result<(), Box<dyn Error>> {
         let request = ChangeDetailRequest {
             playlist_id: request.playlist_id,
             description: Some(request.description),
-            ..Default::default()
+            name: None,
+            public: None,
+            collaborative: None,
         };
 
         self.change_detail(request).await
     }
 
-    pub async fn change_detail(
-        &mut self,
-        request: ChangeDetailRequest,
-    ) -> Result<(), Box<dyn Error>> {
+    pub async fn change_detail(&self, request: ChangeDetailRequest) -> Result<(), Box<dyn Error>> {
         let url = format!(
             "https://api.spotify.com/v1/playlists/{}",
             request.playlist_id
@@ -206,7 +215,7 @@ impl PlaylistClient {
     }
 
     pub async fn create_playlist(
-        &mut self,
+        &self,
         request: CreatePlaylistRequest,
     ) -> Result<Playlist, Box<dyn Error>> {
         let url = format!(
@@ -243,7 +252,7 @@ impl PlaylistClient {
     }
 
     pub async fn get_playlists(
-        &mut self,
+        &self,
         request: GetPlaylistsRequest,
     ) -> Result<PagingObject<SimplePlaylist>, Box<dyn Error>> {
         let url = if let Some(user_id) = request.user_id {
@@ -256,6 +265,7 @@ impl PlaylistClient {
 
         let response = self
             .client
+            .clone()
             .set_limit(request.limit)
             .set_offset(request.offset)
             .send(builder)
@@ -265,10 +275,7 @@ impl PlaylistClient {
         Ok(response.json().await?)
     }
 
-    pub async fn get_image(
-        &mut self,
-        request: GetImageRequest,
-    ) -> Result<Vec<Image>, Box<dyn Error>> {
+    pub async fn get_image(&self, request: GetImageRequest) -> Result<Vec<Image>, Box<dyn Error>> {
         let url = format!(
             "https://api.spotify.com/v1/playlists/{}/images",
             request.playlist_id
@@ -282,7 +289,7 @@ impl PlaylistClient {
     }
 
     pub async fn get_playlist(
-        &mut self,
+        &self,
         request: GetPlaylistRequest,
     ) -> Result<Playlist, Box<dyn Error>> {
         let url = format!(
@@ -294,6 +301,7 @@ impl PlaylistClient {
 
         let response = self
             .client
+            .clone()
             .set_market(request.market)
             .send(builder)
             .await?
@@ -303,7 +311,7 @@ impl PlaylistClient {
     }
 
     pub async fn get_tracks(
-        &mut self,
+        &self,
         request: GetPlaylistTracksRequest,
     ) -> Result<PagingObject<PlaylistTrack>, Box<dyn Error>> {
         let url = format!(
@@ -315,6 +323,7 @@ impl PlaylistClient {
 
         let response = self
             .client
+            .clone()
             .set_limit(request.limit)
             .set_offset(request.offset)
             .set_market(request.market)
@@ -325,72 +334,102 @@ impl PlaylistClient {
         Ok(response.json().await?)
     }
 
-    pub fn remove_items(
-        &mut self,
-        mut request: RemoveItemsRequest,
-    ) -> BoxFuture<'_, Result<Vec<Snapshot>, Box<dyn Error>>> {
-        async move {
-            let url = format!(
-                "https://api.spotify.com/v1/playlists/{}/tracks",
-                request.playlist_id
-            );
-
-            let mut results = Vec::new();
-            if request.tracks.len() > 100 {
-                let tracks = request.tracks.drain(..100).collect();
+    pub async fn get_all_tracks(
+        &self,
+        request: GetPlaylistTracksRequest,
+    ) -> Result<Vec<PlaylistTrack>, Box<dyn Error>> {
+        let mut tracks = Vec::new();
+        let mut offset = request.offset.unwrap_or(0);
+
+        loop {
+            let request = GetPlaylistTracksRequest {
+                limit: Some(100),
+                offset: Some(offset),
+                ..request.clone()
+            };
+
+            let mut page = self.get_tracks(request).await?;
+            let fetched = page.items.len();
+            offset += fetched as u32;
+            tracks.append(&mut page.items);
+
+            if page.next.is_none() || fetched == 0 {
+                break;
+            }
+        }
 
-                let next_request = RemoveItemsRequest {
-                    playlist_id: request.playlist_id.clone(),
-                    tracks,
-                    snapshot_id: request.snapshot_id.clone(),
-                };
+        Ok(tracks)
+    }
 
-                results.append(&mut self.remove_items(next_request).await?);
-                results.append(&mut self.remove_items(request.clone()).await?);
+    pub async fn remove_items(
+        &self,
+        request: RemoveItemsRequest,
+    ) -> Result<Vec<Snapshot>, Box<dyn Error>> {
+        let playlist_id = &request.playlist_id;
+        let snapshot_id = &request.snapshot_id;
+
+        let tracks = request
+            .tracks
+            .into_iter()
+            .map(|(uri, positions)| (uri.uri(), positions))
+            .collect();
+
+        let snapshots =
+            batch::run_with_concurrency(Endpoint::PlaylistItems, 1, tracks, |_, tracks| {
+                self.request_remove_items(playlist_id, tracks, snapshot_id.clone())
+            })
+            .await
+            .into_result()?;
+
+        Ok(snapshots)
+    }
 
-                return Ok(results);
-            }
+    async fn request_remove_items(
+        &self,
+        playlist_id: &PlaylistId,
+        tracks: Vec<(String, Option<Vec<u32>>)>,
+        snapshot_id: Option<String>,
+    ) -> Result<Snapshot, Box<dyn Error>> {
+        let url = format!(
+            "https://api.spotify.com/v1/playlists/{}/tracks",
+            playlist_id
+        );
 
-            let mut json = serde_json::Map::new();
-            let tracks = request
-                .tracks
-                .into_iter()
-                .map(|(uri, positions)| {
-                    let mut object = serde_json::Map::new();
-                    object.insert("uri".to_string(), json!(uri));
+        let mut json = serde_json::Map::new();
+        let tracks = tracks
+            .into_iter()
+            .map(|(uri, positions)| {
+                let mut object = serde_json::Map::new();
+                object.insert("uri".to_string(), json!(uri));
 
-                    if let Some(positions) = positions {
-                        object.insert("positions".to_string(), json!(positions));
-                    }
+                if let Some(positions) = positions {
+                    object.insert("positions".to_string(), json!(positions));
+                }
 
-                    object
-                })
-                .collect();
+                object
+            })
+            .collect();
 
-            json.insert("tracks".to_string(), tracks);
+        json.insert("tracks".to_string(), tracks);
 
-            if let Some(snapshot_id) = request.snapshot_id {
-                json.insert("snapshot_id".to_string(), json!(snapshot_id));
-            }
+        if let Some(snapshot_id) = snapshot_id {
+            json.insert("snapshot_id".to_string(), json!(snapshot_id));
+        }
 
-            let mut headers = HeaderMap::new();
-            headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
 
-            let builder = reqwest::Client::new()
-                .delete(&url)
-                .headers(headers)
-                .json(&json);
+        let builder = reqwest::Client::new()
+            .delete(&url)
+            .headers(headers)
+            .json(&json);
 
-            let response = self.client.send(builder).await?.unwrap();
-            let mut result = response.json().await?;
-            results.append(&mut result);
+        let response = self.client.send(builder).await?.unwrap();
 
-            Ok(results)
-        }
-        .boxed()
+        Ok(response.json().await?)
     }
 
-    pub async fn reorder(&mut self, request: ReorderRequest) -> Result<Snapshot, Box<dyn Error>> {
+    pub async fn reorder(&self, request: ReorderRequest) -> Result<Snapshot, Box<dyn Error>> {
         let url = format!(
             "https://api.spotify.com/v1/playlists/{}/tracks",
             request.playlist_id
@@ -421,14 +460,15 @@ impl PlaylistClient {
         Ok(response.json().await?)
     }
 
-    pub async fn replace(&mut self, request: ReplaceRequest) -> Result<(), Box<dyn Error>> {
+    pub async fn replace(&self, request: ReplaceRequest) -> Result<(), Box<dyn Error>> {
         let url = format!(
             "https://api.spotify.com/v1/playlists/{}/tracks",
             request.playlist_id
         );
 
+        let uris: Vec<String> = request.uris.iter().map(PlayableId::uri).collect();
         let json = json!({
-            "uris": request.uris,
+            "uris": uris,
         });
 
         let mut headers = HeaderMap::new();
@@ -443,56 +483,505 @@ impl PlaylistClient {
 
         Ok(())
     }
+
+    pub async fn backup_playlist(
+        &self,
+        request: BackupPlaylistRequest,
+    ) -> Result<PlaylistBackup, Box<dyn Error>> {
+        let playlist = self
+            .get_playlist(GetPlaylistRequest {
+                playlist_id: request.playlist_id.clone(),
+                market: request.market,
+            })
+            .await?;
+
+        let tracks = self
+            .get_all_tracks(GetPlaylistTracksRequest {
+                playlist_id: request.playlist_id.clone(),
+                limit: None,
+                offset: None,
+                market: request.market,
+            })
+            .await?;
+
+        let images = self
+            .get_image(GetImageRequest {
+                playlist_id: request.playlist_id,
+            })
+            .await?;
+
+        Ok(PlaylistBackup {
+            version: PlaylistBackup::VERSION,
+            exported_at: Utc::now(),
+            playlist,
+            tracks,
+            images,
+        })
+    }
+
+    pub async fn restore_playlist(
+        &self,
+        request: RestorePlaylistRequest,
+    ) -> Result<RestorePlaylistResponse, Box<dyn Error>> {
+        let playlist = self.create_playlist(request.create_request()).await?;
+
+        let mut uris = Vec::new();
+        let mut skipped = Vec::new();
+        for item in request.backup.tracks {
+            match item.track.uri.parse::<PlayableId>() {
+                Ok(uri) if !item.is_local => uris.push(uri),
+                _ => skipped.push(item),
+            }
+        }
+
+        let playlist_id: PlaylistId = playlist.id.parse()?;
+        if !uris.is_empty() {
+            self.add_items(AddItemsRequest {
+                playlist_id,
+                uris,
+                position: None,
+            })
+            .await?;
+        }
+
+        Ok(RestorePlaylistResponse { playlist, skipped })
+    }
+
+    pub async fn sync_playlist(
+        &self,
+        request: SyncPlaylistRequest,
+    ) -> Result<SyncPlaylistResponse, Box<dyn Error>> {
+        let desired: Vec<String> = request.uris.iter().map(PlayableId::uri).collect();
+        let mut applied = Vec::new();
+        let mut attempts = 0;
+
+        loop {
+            let playlist = self
+                .get_playlist(GetPlaylistRequest {
+                    playlist_id: request.playlist_id.clone(),
+                    market: None,
+                })
+                .await?;
+
+            let current: Vec<String> = self
+                .get_all_tracks(GetPlaylistTracksRequest {
+                    playlist_id: request.playlist_id.clone(),
+                    limit: None,
+                    offset: None,
+                    market: None,
+                })
+                .await?
+                .into_iter()
+                .map(|item| item.track.uri)
+                .collect();
+
+            let diff = PlaylistDiff::new(&current, &diff::preserve_local(&current, &desired));
+            if diff.is_empty() {
+                return Ok(SyncPlaylistResponse {
+                    snapshot_id: playlist.snapshot_id,
+                    applied,
+                });
+            }
+
+            if attempts > request.retries {
+                return Err(format!(
+                    "playlist {} changed concurrently while syncing",
+                    request.playlist_id
+                )
+                .into());
+            }
+            attempts += 1;
+
+            match self
+                .apply_diff(&request.playlist_id, &diff, playlist.snapshot_id)
+                .await
+            {
+                Ok(_) => applied.push(diff),
+                Err(error) if attempts <= request.retries => {
+                    match error.downcast_ref::<ApiError>() {
+                        Some(ApiError::RateLimited { retry_after }) => {
+                            delay_for(retry_after.unwrap_or(RATE_LIMIT_DELAY)).await;
+                        }
+                        Some(api_error) if api_error.is_snapshot_conflict() => {}
+                        _ => return Err(error),
+                    }
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    pub(crate) async fn apply_diff(
+        &self,
+        playlist_id: &PlaylistId,
+        diff: &PlaylistDiff,
+        mut snapshot_id: String,
+    ) -> Result<String, Box<dyn Error>> {
+        if !diff.removals.is_empty() {
+            let tracks = diff
+                .removals
+                .iter()
+                .map(|removal| Ok((removal.uri.parse()?, Some(removal.positions.clone()))))
+                .collect::<Result<Vec<_>, IdError>>()?;
+
+            let snapshots = self
+                .remove_items(RemoveItemsRequest {
+                    playlist_id: playlist_id.clone(),
+                    tracks,
+                    snapshot_id: Some(snapshot_id.clone()),
+                })
+                .await?;
+
+            if let Some(snapshot) = snapshots.into_iter().last() {
+                snapshot_id = snapshot.snapshot_id;
+            }
+        }
+
+        for movement in &diff.moves {
+            let snapshot = self
+                .reorder(ReorderRequest {
+                    playlist_id: playlist_id.clone(),
+                    range_start: movement.range_start,
+                    range_length: None,
+                    insert_before: movement.insert_before,
+                    snapshot_id: Some(snapshot_id.clone()),
+                })
+                .await?;
+
+            snapshot_id = snapshot.snapshot_id;
+        }
+
+        for insertion in &diff.insertions {
+            let uris = insertion
+                .uris
+                .iter()
+                .map(|uri| uri.parse())
+                .collect::<Result<Vec<_>, IdError>>()?;
+
+            let snapshots = self
+                .add_items(AddItemsRequest {
+                    playlist_id: playlist_id.clone(),
+                    uris,
+                    position: Some(insertion.position),
+                })
+                .await?;
+
+            if let Some(snapshot) = snapshots.into_iter().last() {
+                snapshot_id = snapshot.snapshot_id;
+            }
+        }
+
+        Ok(snapshot_id)
+    }
+}
+
+pub mod diff {
+    use std::collections::{HashMap, VecDeque};
+
+    use crate::id;
+
+    #[derive(Clone, Debug, Default, Eq, PartialEq)]
+    pub struct PlaylistDiff {
+        pub removals: Vec<Removal>,
+        pub moves: Vec<Move>,
+        pub insertions: Vec<Insertion>,
+    }
+
+    #[derive(Clone, Debug, Default, Eq, PartialEq)]
+    pub struct Removal {
+        pub uri: String,
+        pub positions: Vec<u32>,
+    }
+
+    #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+    pub struct Move {
+        pub range_start: u32,
+        pub insert_before: u32,
+    }
+
+    #[derive(Clone, Debug, Default, Eq, PartialEq)]
+    pub struct Insertion {
+        pub position: u32,
+        pub uris: Vec<String>,
+    }
+
+    pub fn preserve_local(current: &[String], desired: &[String]) -> Vec<String> {
+        let mut merged = desired.to_vec();
+        for (position, uri) in current.iter().enumerate() {
+            if id::is_local_uri(uri) {
+                merged.insert(position.min(merged.len()), uri.clone());
+            }
+        }
+
+        merged
+    }
+
+    impl PlaylistDiff {
+        pub fn new(current: &[String], desired: &[String]) -> Self {
+            let mut targets: HashMap<&str, VecDeque<usize>> = HashMap::new();
+            for (index, uri) in desired.iter().enumerate() {
+                targets.entry(uri).or_default().push_back(index);
+            }
+
+            let mut removals: Vec<Removal> = Vec::new();
+            let mut kept = Vec::new();
+            for (position, uri) in current.iter().enumerate() {
+                match targets.get_mut(uri.as_str()).and_then(VecDeque::pop_front) {
+                    Some(target) => kept.push(target),
+                    None => match removals.iter_mut().find(|removal| &removal.uri == uri) {
+                        Some(removal) => removal.positions.push(position as u32),
+                        None => removals.push(Removal {
+                            uri: uri.clone(),
+                            positions: vec![position as u32],
+                        }),
+                    },
+                }
+            }
+
+            let moves = moves(kept);
+
+            let mut missing: Vec<usize> = targets.into_values().flatten().collect();
+            missing.sort_unstable();
+
+            let mut insertions: Vec<Insertion> = Vec::new();
+            for target in missing {
+                match insertions.last_mut() {
+                    Some(insertion)
+                        if insertion.position as usize + insertion.uris.len() == target =>
+                    {
+                        insertion.uris.push(desired[target].clone());
+                    }
+                    _ => insertions.push(Insertion {
+                        position: target as u32,
+                        uris: vec![desired[target].clone()],
+                    }),
+                }
+            }
+
+            PlaylistDiff {
+                removals,
+                moves,
+                insertions,
+            }
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.removals.is_empty() && self.moves.is_empty() && self.insertions.is_empty()
+        }
+
+        pub fn apply(&self, current: &[String]) -> Vec<String> {
+            let removed: Vec<u32> = self
+                .removals
+                .iter()
+                .flat_map(|removal| removal.positions.iter().copied())
+                .collect();
+
+            let mut items: Vec<String> = current
+                .iter()
+                .enumerate()
+                .filter(|(position, _)| !removed.contains(&(*position as u32)))
+                .map(|(_, uri)| uri.clone())
+                .collect();
+
+            for movement in &self.moves {
+                let item = items.remove(movement.range_start as usize);
+                let insert_before = if movement.insert_before > movement.range_start {
+                    movement.insert_before - 1
+                } else {
+                    movement.insert_before
+                };
+                items.insert(insert_before as usize, item);
+            }
+
+            for insertion in &self.insertions {
+                let position = insertion.position as usize;
+                items.splice(position..position, insertion.uris.iter().cloned());
+            }
+
+            items
+        }
+    }
+
+    fn moves(mut items: Vec<usize>) -> Vec<Move> {
+        let mut placed = vec![false; items.len()];
+        for index in longest_increasing_subsequence(&items) {
+            placed[index] = true;
+        }
+
+        let mut pending: Vec<usize> = items
+            .iter()
+            .zip(&placed)
+            .filter(|(_, placed)| !**placed)
+            .map(|(target, _)| *target)
+            .collect();
+        pending.sort_unstable();
+
+        let mut moves = Vec::new();
+        for target in pending {
+            let range_start = items.iter().position(|item| *item == target).unwrap();
+            let insert_before = items
+                .iter()
+                .zip(&placed)
+                .position(|(item, placed)| *placed && *item > target)
+                .unwrap_or(items.len());
+
+            let item = items.remove(range_start);
+            placed.remove(range_start);
+            let index = if insert_before > range_start {
+                insert_before - 1
+            } else {
+                insert_before
+            };
+            items.insert(index, item);
+            placed.insert(index, true);
+
+            if index != range_start {
+                moves.push(Move {
+                    range_start: range_start as u32,
+                    insert_before: insert_before as u32,
+                });
+            }
+        }
+
+        moves
+    }
+
+    fn longest_increasing_subsequence(items: &[usize]) -> Vec<usize> {
+        let mut tails: Vec<usize> = Vec::new();
+        let mut previous = vec![None; items.len()];
+
+        for (index, item) in items.iter().enumerate() {
+            let length = tails.partition_point(|tail| items[*tail] < *item);
+            if length > 0 {
+                previous[index] = Some(tails[length - 1]);
+            }
+
+            if length == tails.len() {
+                tails.push(index);
+            } else {
+                tails[length] = index;
+            }
+        }
+
+        let mut sequence = Vec::new();
+        let mut current = tails.last().copied();
+        while let Some(index) = current {
+            sequence.push(index);
+            current = previous[index];
+        }
+        sequence.reverse();
+
+        sequence
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct Snapshot {
-    snapshot_id: String,
+    pub snapshot_id: String,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PlaylistBackup {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub playlist: Playlist,
+    pub tracks: Vec<PlaylistTrack>,
+    pub images: Vec<Image>,
+}
+
+impl PlaylistBackup {
+    pub const VERSION: u32 = 1;
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        object::from_versioned_json(json, "playlist backup", PlaylistBackup::VERSION)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BackupPlaylistRequest {
+    pub playlist_id: PlaylistId,
+    pub market: Option<CountryCode>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RestorePlaylistRequest {
+    pub user_id: UserId,
+    pub name: Option<String>,
+    pub backup: PlaylistBackup,
+}
+
+impl RestorePlaylistRequest {
+    pub fn create_request(&self) -> CreatePlaylistRequest {
+        let playlist = &self.backup.playlist;
+        let public = if playlist.collaborative {
+            Some(false)
+        } else {
+            playlist.public
+        };
+
+        CreatePlaylistRequest {
+            user_id: self.user_id.clone(),
+            name: self.name.clone().unwrap_or_else(|| playlist.name.clone()),
+            public,
+            collaborative: Some(playlist.collaborative),
+            description: playlist.description.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RestorePlaylistResponse {
+    pub playlist: Playlist,
+    pub skipped: Vec<PlaylistTrack>,
+}
+
+#[derive(Clone, Debug)]
 pub struct AddItemsRequest {
-    pub playlist_id: String,
-    pub uris: Vec<String>,
+    pub playlist_id: PlaylistId,
+    pub uris: Vec<PlayableId>,
     pub position: Option<u32>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct ChangeNameRequest {
-    pub playlist_id: String,
+    pub playlist_id: PlaylistId,
     pub name: String,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct ChangePublicRequest {
-    pub playlist_id: String,
+    pub playlist_id: PlaylistId,
     pub public: bool,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct ChangeCollaborativeRequest {
-    pub playlist_id: String,
+    pub playlist_id: PlaylistId,
     pub collaborative: bool,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct ChangeDescriptionRequest {
-    pub playlist_id: String,
+    pub playlist_id: PlaylistId,
     pub description: String,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct ChangeDetailRequest {
-    pub playlist_id: String,
+    pub playlist_id: PlaylistId,
     pub name: Option<String>,
     pub public: Option<bool>,
     pub collaborative: Option<bool>,
     pub description: Option<String>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct CreatePlaylistRequest {
-    pub user_id: String,
+    pub user_id: UserId,
     pub name: String,
     pub public: Option<bool>,
     pub collaborative: Option<bool>,
@@ -501,48 +990,61 @@ pub struct CreatePlaylistRequest {
 
 #[derive(Clone, Debug, Default)]
 pub struct GetPlaylistsRequest {
-    pub user_id: Option<String>,
+    pub user_id: Option<UserId>,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct GetImageRequest {
-    pub playlist_id: String,
+    pub playlist_id: PlaylistId,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct GetPlaylistRequest {
-    pub playlist_id: String,
+    pub playlist_id: PlaylistId,
     pub market: Option<CountryCode>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct GetPlaylistTracksRequest {
-    pub playlist_id: String,
+    pub playlist_id: PlaylistId,
     pub limit: Option<u32>,
     pub offset: Option<u32>,
     pub market: Option<CountryCode>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct RemoveItemsRequest {
-    pub playlist_id: String,
-    pub tracks: Vec<(String, Option<Vec<u32>>)>,
+    pub playlist_id: PlaylistId,
+    pub tracks: Vec<(PlayableId, Option<Vec<u32>>)>,
     pub snapshot_id: Option<String>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct ReorderRequest {
-    pub playlist_id: String,
+    pub playlist_id: PlaylistId,
     pub range_start: u32,
     pub range_length: Option<u32>,
     pub insert_before: u32,
     pub snapshot_id: Option<String>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct ReplaceRequest {
-    pub playlist_id: String,
-    pub uris: Vec<String>,
+    pub playlist_id: PlaylistId,
+    pub uris: Vec<PlayableId>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SyncPlaylistRequest {
+    pub playlist_id: PlaylistId,
+    pub uris: Vec<PlayableId>,
+    pub retries: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SyncPlaylistResponse {
+    pub snapshot_id: String,
+    pub applied: Vec<PlaylistDiff>,
 }
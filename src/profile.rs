@@ -0,0 +1,363 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+};
+
+use isocountry::CountryCode;
+
+use crate::{
+    browse::TrackAttribute,
+    id::{PlayableId, PlaylistId, UserId},
+    playlist::{
+        AddItemsRequest, CreatePlaylistRequest, GetPlaylistTracksRequest, Playlist, PlaylistClient,
+        PlaylistTrack,
+    },
+    track::{AudioFeature, TrackClient},
+};
+
+pub const TEMPO_BUCKET: u32 = 10;
+
+#[derive(Clone, Debug)]
+pub struct ProfiledTrack {
+    pub item: PlaylistTrack,
+    pub feature: Option<AudioFeature>,
+}
+
+impl ProfiledTrack {
+    pub fn release_year(&self) -> Option<i32> {
+        let release_date = self.item.track.album.as_ref()?.release_date.as_ref()?;
+
+        release_date.get(..4)?.parse().ok()
+    }
+
+    pub fn attribute(&self, attribute: &TrackAttribute) -> Option<f64> {
+        let feature = self.feature.as_ref();
+        let value = match attribute {
+            TrackAttribute::Acousticness(_) => f64::from(feature?.acousticness),
+            TrackAttribute::Danceability(_) => f64::from(feature?.danceability),
+            TrackAttribute::DurationMs(_) => f64::from(self.item.track.duration_ms),
+            TrackAttribute::Energy(_) => f64::from(feature?.energy),
+            TrackAttribute::Instrumentalness(_) => f64::from(feature?.instrumentalness),
+            TrackAttribute::Key(_) => match feature?.key {
+                -1 => return None,
+                key => f64::from(key),
+            },
+            TrackAttribute::Liveness(_) => f64::from(feature?.liveness),
+            TrackAttribute::Loudness(_) => f64::from(feature?.loudness),
+            TrackAttribute::Mode(_) => f64::from(feature?.mode),
+            TrackAttribute::Popularity(_) => f64::from(self.item.track.popularity?),
+            TrackAttribute::Speechiness(_) => f64::from(feature?.speechiness),
+            TrackAttribute::Tempo(_) => f64::from(feature?.tempo),
+            TrackAttribute::TimeSignature(_) => f64::from(feature?.time_signature),
+            TrackAttribute::Valence(_) => f64::from(feature?.valence),
+        };
+
+        Some(value)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum TrackFilter {
+    Min(TrackAttribute),
+    Max(TrackAttribute),
+    Explicit(bool),
+    ReleasedBetween(i32, i32),
+    Artist(String),
+}
+
+impl TrackFilter {
+    pub fn between(min: TrackAttribute, max: TrackAttribute) -> Vec<TrackFilter> {
+        vec![TrackFilter::Min(min), TrackFilter::Max(max)]
+    }
+
+    pub fn matches(&self, track: &ProfiledTrack) -> bool {
+        match self {
+            TrackFilter::Min(attribute) => track
+                .attribute(attribute)
+                .is_some_and(|value| value >= attribute.as_f64()),
+            TrackFilter::Max(attribute) => track
+                .attribute(attribute)
+                .is_some_and(|value| value <= attribute.as_f64()),
+            TrackFilter::Explicit(explicit) => track.item.track.explicit == *explicit,
+            TrackFilter::ReleasedBetween(from, to) => track
+                .release_year()
+                .is_some_and(|year| year >= *from && year <= *to),
+            TrackFilter::Artist(artist) => track
+                .item
+                .track
+                .artists
+                .iter()
+                .any(|a| &a.id == artist || a.name.eq_ignore_ascii_case(artist)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FeatureAverages {
+    pub acousticness: f64,
+    pub danceability: f64,
+    pub energy: f64,
+    pub instrumentalness: f64,
+    pub liveness: f64,
+    pub loudness: f64,
+    pub speechiness: f64,
+    pub tempo: f64,
+    pub valence: f64,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ArtistCount {
+    pub id: String,
+    pub name: String,
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ProfileStats {
+    pub track_count: usize,
+    pub analyzed_count: usize,
+    pub total_duration_ms: u64,
+    pub explicit_ratio: f64,
+    pub averages: Option<FeatureAverages>,
+    pub tempo_histogram: BTreeMap<u32, usize>,
+    pub release_years: BTreeMap<i32, usize>,
+    pub top_artists: Vec<ArtistCount>,
+    pub skipped: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PlaylistProfile {
+    pub tracks: Vec<ProfiledTrack>,
+}
+
+impl PlaylistProfile {
+    pub fn new(items: Vec<PlaylistTrack>, features: Vec<AudioFeature>) -> Self {
+        let features: HashMap<String, AudioFeature> = features
+            .into_iter()
+            .map(|feature| (feature.id.clone(), feature))
+            .collect();
+
+        let tracks = items
+            .into_iter()
+            .map(|item| {
+                let feature = if item.is_local {
+                    None
+                } else {
+                    features.get(&item.track.id).cloned()
+                };
+
+                ProfiledTrack { item, feature }
+            })
+            .collect();
+
+        PlaylistProfile { tracks }
+    }
+
+    pub fn filter(&self, filters: &[TrackFilter]) -> Vec<&ProfiledTrack> {
+        self.tracks
+            .iter()
+            .filter(|track| filters.iter().all(|filter| filter.matches(track)))
+            .collect()
+    }
+
+    pub fn stats(&self) -> ProfileStats {
+        let track_count = self.tracks.len();
+        let features: Vec<&AudioFeature> = self
+            .tracks
+            .iter()
+            .filter_map(|track| track.feature.as_ref())
+            .collect();
+
+        let explicit = self
+            .tracks
+            .iter()
+            .filter(|track| track.item.track.explicit)
+            .count();
+
+        let skipped = self
+            .tracks
+            .iter()
+            .filter(|track| track.feature.is_none())
+            .map(|track| track.item.track.uri.clone())
+            .collect();
+
+        let mut tempo_histogram = BTreeMap::new();
+        for feature in &features {
+            let bucket = (feature.tempo.max(0.0) as u32 / TEMPO_BUCKET) * TEMPO_BUCKET;
+            *tempo_histogram.entry(bucket).or_insert(0) += 1;
+        }
+
+        let mut release_years = BTreeMap::new();
+        for year in self.tracks.iter().filter_map(ProfiledTrack::release_year) {
+            *release_years.entry(year).or_insert(0) += 1;
+        }
+
+        let mut artists: HashMap<&str, ArtistCount> = HashMap::new();
+        for artist in self
+            .tracks
+            .iter()
+            .flat_map(|track| &track.item.track.artists)
+        {
+            artists
+                .entry(&artist.id)
+                .or_insert_with(|| ArtistCount {
+                    id: artist.id.clone(),
+                    name: artist.name.clone(),
+                    count: 0,
+                })
+                .count += 1;
+        }
+        let mut top_artists: Vec<ArtistCount> = artists.into_values().collect();
+        top_artists.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+
+        ProfileStats {
+            track_count,
+            analyzed_count: features.len(),
+            total_duration_ms: self
+                .tracks
+                .iter()
+                .map(|track| u64::from(track.item.track.duration_ms))
+                .sum(),
+            explicit_ratio: if track_count == 0 {
+                0.0
+            } else {
+                explicit as f64 / track_count as f64
+            },
+            averages: averages(&features),
+            tempo_histogram,
+            release_years,
+            top_artists,
+            skipped,
+        }
+    }
+}
+
+fn averages(features: &[&AudioFeature]) -> Option<FeatureAverages> {
+    if features.is_empty() {
+        return None;
+    }
+
+    let mean = |value: fn(&AudioFeature) -> f32| {
+        features
+            .iter()
+            .map(|feature| f64::from(value(feature)))
+            .sum::<f64>()
+            / features.len() as f64
+    };
+
+    Some(FeatureAverages {
+        acousticness: mean(|f| f.acousticness),
+        danceability: mean(|f| f.danceability),
+        energy: mean(|f| f.energy),
+        instrumentalness: mean(|f| f.instrumentalness),
+        liveness: mean(|f| f.liveness),
+        loudness: mean(|f| f.loudness),
+        speechiness: mean(|f| f.speechiness),
+        tempo: mean(|f| f.tempo),
+        valence: mean(|f| f.valence),
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct AnalyzePlaylistRequest {
+    pub playlist_id: PlaylistId,
+    pub market: Option<CountryCode>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DerivePlaylistRequest {
+    pub playlist_id: PlaylistId,
+    pub user_id: UserId,
+    pub name: String,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    pub filters: Vec<TrackFilter>,
+    pub market: Option<CountryCode>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DerivePlaylistResponse {
+    pub playlist: Playlist,
+    pub tracks: Vec<ProfiledTrack>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PlaylistAnalyzer {
+    playlist: PlaylistClient,
+    track: TrackClient,
+}
+
+impl PlaylistAnalyzer {
+    pub fn new(access_token: &str, refresh_token: &str) -> Self {
+        PlaylistAnalyzer {
+            playlist: PlaylistClient::new(access_token, refresh_token),
+            track: TrackClient::new(access_token, refresh_token),
+        }
+    }
+
+    pub async fn analyze(
+        &self,
+        request: AnalyzePlaylistRequest,
+    ) -> Result<PlaylistProfile, Box<dyn Error>> {
+        let items = self
+            .playlist
+            .get_all_tracks(GetPlaylistTracksRequest {
+                playlist_id: request.playlist_id,
+                limit: None,
+                offset: None,
+                market: request.market,
+            })
+            .await?;
+
+        let features = self.track.get_playlist_features(&items).await?;
+
+        Ok(PlaylistProfile::new(items, features))
+    }
+
+    pub async fn derive_playlist(
+        &self,
+        request: DerivePlaylistRequest,
+    ) -> Result<DerivePlaylistResponse, Box<dyn Error>> {
+        let profile = self
+            .analyze(AnalyzePlaylistRequest {
+                playlist_id: request.playlist_id,
+                market: request.market,
+            })
+            .await?;
+
+        let tracks: Vec<ProfiledTrack> = profile
+            .filter(&request.filters)
+            .into_iter()
+            .filter(|track| !track.item.is_local)
+            .cloned()
+            .collect();
+
+        let uris = tracks
+            .iter()
+            .map(|track| track.item.track.uri.parse())
+            .collect::<Result<Vec<PlayableId>, _>>()?;
+
+        let playlist = self
+            .playlist
+            .create_playlist(CreatePlaylistRequest {
+                user_id: request.user_id,
+                name: request.name,
+                public: request.public,
+                collaborative: None,
+                description: request.description,
+            })
+            .await?;
+
+        if !uris.is_empty() {
+            self.playlist
+                .add_items(AddItemsRequest {
+                    playlist_id: playlist.id.parse()?,
+                    uris,
+                    position: None,
+                })
+                .await?;
+        }
+
+        Ok(DerivePlaylistResponse { playlist, tracks })
+    }
+}
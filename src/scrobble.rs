@@ -0,0 +1,273 @@
+use std::{
+    error::Error,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use isocountry::CountryCode;
+use serde::Serialize;
+
+use crate::{
+    error::ApiError,
+    player::{
+        Context, CurrentlyPlayingObject, Device, GetCurrentlyRequest, ObjectType, PlayableItem,
+        PlayerClient,
+    },
+};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Scrobble {
+    pub item: PlayableItem,
+    pub context: Option<Context>,
+    pub device: Option<Device>,
+    pub started_at: DateTime<Utc>,
+    pub scrobbled_at: DateTime<Utc>,
+    pub played_ms: u32,
+}
+
+pub trait ScrobbleSink {
+    fn record(&mut self, scrobble: &Scrobble) -> Result<(), Box<dyn Error>>;
+}
+
+impl ScrobbleSink for Vec<Scrobble> {
+    fn record(&mut self, scrobble: &Scrobble) -> Result<(), Box<dyn Error>> {
+        self.push(scrobble.clone());
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FileSink {
+    path: PathBuf,
+}
+
+impl FileSink {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        FileSink {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl ScrobbleSink for FileSink {
+    fn record(&mut self, scrobble: &Scrobble) -> Result<(), Box<dyn Error>> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(scrobble)?)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ScrobbleThreshold {
+    pub fraction: f64,
+    pub max: Duration,
+    pub min_duration: Duration,
+}
+
+impl Default for ScrobbleThreshold {
+    fn default() -> Self {
+        ScrobbleThreshold {
+            fraction: 0.5,
+            max: Duration::from_secs(240),
+            min_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+impl ScrobbleThreshold {
+    pub fn required(&self, duration_ms: u32) -> Option<Duration> {
+        let duration = Duration::from_millis(u64::from(duration_ms));
+        if duration < self.min_duration {
+            return None;
+        }
+
+        Some(duration.mul_f64(self.fraction).min(self.max))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Play {
+    item: PlayableItem,
+    context: Option<Context>,
+    device: Option<Device>,
+    started_at: DateTime<Utc>,
+    played: Duration,
+    progress_ms: u32,
+    is_playing: bool,
+    scrobbled: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ScrobbleTracker {
+    threshold: ScrobbleThreshold,
+    play: Option<Play>,
+}
+
+impl ScrobbleTracker {
+    pub const RESTART_TOLERANCE_MS: u32 = 3000;
+
+    pub fn new(threshold: ScrobbleThreshold) -> Self {
+        ScrobbleTracker {
+            threshold,
+            play: None,
+        }
+    }
+
+    pub fn current_uri(&self) -> Option<&str> {
+        self.play.as_ref().map(|play| play.item.uri())
+    }
+
+    pub fn observe(
+        &mut self,
+        current: Option<&CurrentlyPlayingObject>,
+        device: Option<&Device>,
+        elapsed: Duration,
+        now: DateTime<Utc>,
+    ) -> Option<Scrobble> {
+        let (object, item) = match current.and_then(|object| Some((object, object.get_item()?))) {
+            Some(current) => current,
+            None => {
+                self.play = None;
+                return None;
+            }
+        };
+        let progress_ms = object.progress_ms.unwrap_or_default();
+
+        let restarted = match &self.play {
+            Some(play) => {
+                play.item.uri() != item.uri()
+                    || (progress_ms < ScrobbleTracker::RESTART_TOLERANCE_MS
+                        && play.progress_ms > progress_ms + ScrobbleTracker::RESTART_TOLERANCE_MS)
+            }
+            None => true,
+        };
+
+        if restarted {
+            self.play = Some(Play {
+                item,
+                context: object.context.clone(),
+                device: device.cloned(),
+                started_at: now - chrono::Duration::milliseconds(i64::from(progress_ms)),
+                played: Duration::default(),
+                progress_ms,
+                is_playing: object.is_playing,
+                scrobbled: false,
+            });
+
+            return None;
+        }
+
+        let play = self.play.as_mut()?;
+        if play.is_playing {
+            play.played += elapsed;
+        }
+        play.progress_ms = progress_ms;
+        play.is_playing = object.is_playing;
+        if device.is_some() {
+            play.device = device.cloned();
+        }
+
+        let required = self.threshold.required(play.item.duration_ms())?;
+        if play.scrobbled || play.played < required {
+            return None;
+        }
+        play.scrobbled = true;
+
+        Some(Scrobble {
+            item: play.item.clone(),
+            context: play.context.clone(),
+            device: play.device.clone(),
+            started_at: play.started_at,
+            scrobbled_at: now,
+            played_ms: play.played.as_millis() as u32,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ScrobbleRequest {
+    pub interval: Duration,
+    pub threshold: ScrobbleThreshold,
+    pub market: Option<CountryCode>,
+}
+
+impl Default for ScrobbleRequest {
+    fn default() -> Self {
+        ScrobbleRequest {
+            interval: Duration::from_secs(5),
+            threshold: ScrobbleThreshold::default(),
+            market: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Scrobbler {
+    client: PlayerClient,
+    request: ScrobbleRequest,
+    tracker: ScrobbleTracker,
+    polled_at: Option<Instant>,
+}
+
+impl Scrobbler {
+    pub fn new(client: PlayerClient, request: ScrobbleRequest) -> Self {
+        Scrobbler {
+            client,
+            tracker: ScrobbleTracker::new(request.threshold.clone()),
+            request,
+            polled_at: None,
+        }
+    }
+
+    pub async fn poll(&mut self) -> Result<Option<Scrobble>, Box<dyn Error>> {
+        let current = self
+            .client
+            .get_current_playback(GetCurrentlyRequest {
+                market: self.request.market,
+                additional_types: Some(vec![ObjectType::Track, ObjectType::Episode]),
+            })
+            .await?;
+
+        let now = Instant::now();
+        let elapsed = self
+            .polled_at
+            .map(|polled_at| now.duration_since(polled_at))
+            .unwrap_or_default();
+        self.polled_at = Some(now);
+
+        Ok(self.tracker.observe(
+            current.as_ref().map(|context| &context.object),
+            current.as_ref().map(|context| &context.device),
+            elapsed,
+            Utc::now(),
+        ))
+    }
+
+    pub async fn run<S: ScrobbleSink>(&mut self, sink: &mut S) -> Result<(), Box<dyn Error>> {
+        loop {
+            let interval = match self.poll().await {
+                Ok(Some(scrobble)) => {
+                    sink.record(&scrobble)?;
+                    self.request.interval
+                }
+                Ok(None) => self.request.interval,
+                Err(error) => match error.downcast_ref::<ApiError>() {
+                    Some(ApiError::RateLimited { retry_after }) => retry_after
+                        .unwrap_or(self.request.interval * 2)
+                        .max(self.request.interval),
+                    _ => return Err(error),
+                },
+            };
+
+            tokio::time::delay_for(interval).await;
+        }
+    }
+}
@@ -47,7 +47,7 @@ impl SearchClient {
     }
 
     pub fn set_matching(&mut self, object_type: ObjectType, name: &str) -> &mut Self {
-        let query = format!("{}:{}", object_type.to_string(), name);
+        let query = format!("{}:{}", object_type, name);
         self.query.push(query);
         self
     }
@@ -79,26 +79,24 @@ impl SearchClient {
         self
     }
 
-    pub async fn search_album(&mut self) -> Result<PagingObject<SimpleAlbum>, Box<dyn Error>> {
+    pub async fn search_album(&self) -> Result<PagingObject<SimpleAlbum>, Box<dyn Error>> {
         self.search(ObjectType::Album).await
     }
 
-    pub async fn search_artist(&mut self) -> Result<PagingObject<Artist>, Box<dyn Error>> {
+    pub async fn search_artist(&self) -> Result<PagingObject<Artist>, Box<dyn Error>> {
         self.search(ObjectType::Artist).await
     }
 
-    pub async fn search_playlist(
-        &mut self,
-    ) -> Result<PagingObject<SimplePlaylist>, Box<dyn Error>> {
+    pub async fn search_playlist(&self) -> Result<PagingObject<SimplePlaylist>, Box<dyn Error>> {
         self.search(ObjectType::Playlist).await
     }
 
-    pub async fn search_track(&mut self) -> Result<PagingObject<Track>, Box<dyn Error>> {
+    pub async fn search_track(&self) -> Result<PagingObject<Track>, Box<dyn Error>> {
         self.search(ObjectType::Track).await
     }
 
     async fn search<T: DeserializeOwned + Clone>(
-        &mut self,
+        &self,
         object_type: ObjectType,
     ) -> Result<T, Box<dyn Error>> {
         let builder = reqwest::Client::new()
@@ -107,6 +105,7 @@ impl SearchClient {
 
         let response = self
             .client
+            .clone()
             .set_limit(self.limit)
             .set_offset(self.offset)
             .set_market(self.market)
@@ -115,7 +114,7 @@ impl SearchClient {
             .unwrap();
 
         let mut value: serde_json::Value = serde_json::from_str(&response.text().await?).unwrap();
-        let key = format!("{}s", object_type.to_string());
+        let key = format!("{}s", object_type);
 
         Ok(serde_json::from_value(value[key].take())?)
     }
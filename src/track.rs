@@ -1,10 +1,17 @@
-use std::error::Error;
+use std::{collections::HashSet, error::Error};
 
-use futures::future::{BoxFuture, FutureExt};
 use isocountry::CountryCode;
 use serde::{Deserialize, Serialize};
 
-use crate::{album::SimpleAlbum, artist::SimpleArtist, RequestClient};
+use crate::{
+    album::SimpleAlbum,
+    artist::SimpleArtist,
+    batch::{self, Endpoint},
+    harmonic::Key,
+    id::{self, TrackId},
+    playlist::PlaylistTrack,
+    RequestClient,
+};
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Track {
@@ -58,8 +65,8 @@ impl TrackClient {
     }
 
     pub async fn get_audio_analysis(
-        &mut self,
-        track_id: &str,
+        &self,
+        track_id: &TrackId,
     ) -> Result<AudioAnalysis, Box<dyn Error>> {
         let url = format!("https://api.spotify.com/v1/audio-analysis/{}", track_id);
         let builder = reqwest::Client::new().get(&url);
@@ -69,8 +76,8 @@ impl TrackClient {
     }
 
     pub async fn get_audio_feature(
-        &mut self,
-        track_id: &str,
+        &self,
+        track_id: &TrackId,
     ) -> Result<AudioFeature, Box<dyn Error>> {
         let url = format!("https://api.spotify.com/v1/audio-features/{}", track_id);
         let builder = reqwest::Client::new().get(&url);
@@ -79,78 +86,113 @@ impl TrackClient {
         Ok(response.json().await?)
     }
 
-    pub fn get_audio_features(
-        &mut self,
-        mut track_ids: Vec<String>,
-    ) -> BoxFuture<'_, Result<Vec<AudioFeature>, Box<dyn Error>>> {
-        async move {
-            let mut features = Vec::new();
-            if track_ids.len() > 100 {
-                let drained = track_ids.drain(..100).collect();
-                features.append(&mut self.get_audio_features(drained).await?);
-                features.append(&mut self.get_audio_features(track_ids.clone()).await?);
+    pub async fn get_audio_features(
+        &self,
+        track_ids: Vec<TrackId>,
+    ) -> Result<Vec<Option<AudioFeature>>, Box<dyn Error>> {
+        let ids = id::into_strings(track_ids);
+        let features = batch::run(Endpoint::AudioFeatures, ids, |_, ids| {
+            self.request_audio_features(ids)
+        })
+        .await
+        .into_items()?;
 
-                return Ok(features);
-            }
+        Ok(features)
+    }
 
-            let builder = reqwest::Client::new()
-                .get("https://api.spotify.com/v1/audio-features")
-                .query(&[("ids", track_ids.join(","))]);
+    pub async fn get_playlist_features(
+        &self,
+        items: &[PlaylistTrack],
+    ) -> Result<Vec<AudioFeature>, Box<dyn Error>> {
+        let mut unique = HashSet::new();
+        let ids = items
+            .iter()
+            .filter(|item| !item.is_local && unique.insert(item.track.id.as_str()))
+            .map(|item| item.track.id.parse())
+            .collect::<Result<Vec<TrackId>, _>>()?;
 
-            let response = self.client.send(builder).await?.unwrap();
-            let mut response: GetAudioFeaturesResponse = response.json().await?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
 
-            features.append(&mut response.audio_features);
+        let features = self.get_audio_features(ids).await?;
 
-            Ok(features)
-        }
-        .boxed()
+        Ok(features.into_iter().flatten().collect())
+    }
+
+    async fn request_audio_features(
+        &self,
+        track_ids: Vec<String>,
+    ) -> Result<Vec<Option<AudioFeature>>, Box<dyn Error>> {
+        let builder = reqwest::Client::new()
+            .get("https://api.spotify.com/v1/audio-features")
+            .query(&[("ids", track_ids.join(","))]);
+
+        let response = self.client.send(builder).await?.unwrap();
+        let response: GetAudioFeaturesResponse = response.json().await?;
+
+        Ok(response.audio_features)
     }
 
     pub async fn get_track(
-        &mut self,
-        track_id: &str,
+        &self,
+        track_id: &TrackId,
         market: Option<CountryCode>,
     ) -> Result<Track, Box<dyn Error>> {
         let url = format!("https://api.spotify.com/v1/tracks/{}", track_id);
         let builder = reqwest::Client::new().get(&url);
-        let response = self.client.set_market(market).send(builder).await?.unwrap();
+        let response = self
+            .client
+            .clone()
+            .set_market(market)
+            .send(builder)
+            .await?
+            .unwrap();
 
         Ok(response.json().await?)
     }
 
-    pub fn get_tracks(
-        &mut self,
-        mut track_ids: Vec<String>,
+    pub async fn get_tracks(
+        &self,
+        track_ids: Vec<TrackId>,
         market: Option<CountryCode>,
-    ) -> BoxFuture<'_, Result<Vec<Track>, Box<dyn Error>>> {
-        async move {
-            let mut tracks = Vec::new();
-            if track_ids.len() > 50 {
-                let drained = track_ids.drain(..50).collect();
-                tracks.append(&mut self.get_tracks(drained, market).await?);
-                tracks.append(&mut self.get_tracks(track_ids.clone(), market).await?);
-
-                return Ok(tracks);
-            }
+    ) -> Result<Vec<Track>, Box<dyn Error>> {
+        let ids = id::into_strings(track_ids);
+        let tracks = batch::run(Endpoint::Tracks, ids, |_, ids| {
+            self.request_tracks(ids, market)
+        })
+        .await
+        .into_items()?;
 
-            let builder = reqwest::Client::new()
-                .get("https://api.spotify.com/v1/tracks")
-                .query(&[("ids", track_ids.join(","))]);
+        Ok(tracks)
+    }
 
-            let response = self.client.set_market(market).send(builder).await?.unwrap();
-            let mut results: GetTracksResponse = response.json().await?;
+    async fn request_tracks(
+        &self,
+        track_ids: Vec<String>,
+        market: Option<CountryCode>,
+    ) -> Result<Vec<Track>, Box<dyn Error>> {
+        let builder = reqwest::Client::new()
+            .get("https://api.spotify.com/v1/tracks")
+            .query(&[("ids", track_ids.join(","))]);
 
-            tracks.append(&mut results.tracks);
+        let response = self
+            .client
+            .clone()
+            .set_market(market)
+            .send(builder)
+            .await?
+            .unwrap();
+        let results: GetTracksResponse = response.json().await?;
 
-            Ok(tracks)
-        }
-        .boxed()
+        Ok(results.tracks)
     }
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct AudioAnalysis {
+    pub meta: AnalysisMeta,
+    pub track: AnalysisTrack,
     pub bars: Vec<TimeInterval>,
     pub beats: Vec<TimeInterval>,
     pub sections: Vec<Section>,
@@ -158,6 +200,237 @@ pub struct AudioAnalysis {
     pub tatums: Vec<TimeInterval>,
 }
 
+impl AudioAnalysis {
+    pub fn duration(&self) -> f64 {
+        if self.track.duration > 0.0 {
+            return self.track.duration;
+        }
+
+        self.segments
+            .iter()
+            .map(Segment::end)
+            .chain(self.beats.iter().map(TimeInterval::end))
+            .fold(0.0, f64::max)
+    }
+
+    pub fn bar_at(&self, ms: u32) -> Option<(usize, &TimeInterval)> {
+        interval_at(&self.bars, ms, |bar| (bar.start, bar.duration))
+    }
+
+    pub fn beat_at(&self, ms: u32) -> Option<(usize, &TimeInterval)> {
+        interval_at(&self.beats, ms, |beat| (beat.start, beat.duration))
+    }
+
+    pub fn tatum_at(&self, ms: u32) -> Option<(usize, &TimeInterval)> {
+        interval_at(&self.tatums, ms, |tatum| (tatum.start, tatum.duration))
+    }
+
+    pub fn section_at(&self, ms: u32) -> Option<(usize, &Section)> {
+        interval_at(&self.sections, ms, |section| {
+            (section.start, section.duration)
+        })
+    }
+
+    pub fn segment_at(&self, ms: u32) -> Option<(usize, &Segment)> {
+        interval_at(&self.segments, ms, |segment| {
+            (segment.start, segment.duration)
+        })
+    }
+
+    pub fn section_boundaries(&self) -> Vec<f64> {
+        self.sections
+            .iter()
+            .skip(1)
+            .map(|section| section.start)
+            .collect()
+    }
+
+    pub fn loudness_envelope(&self, window_ms: u32) -> Vec<f64> {
+        self.windows(window_ms)
+            .map(|(start, end)| {
+                weighted_mean(self.segments.iter().map(|segment| {
+                    (
+                        overlap(segment.start, segment.end(), start, end),
+                        segment.loudness_max,
+                    )
+                }))
+                .unwrap_or(f64::NAN)
+            })
+            .collect()
+    }
+
+    pub fn tempo_curve(&self, window_ms: u32) -> Vec<f64> {
+        self.windows(window_ms)
+            .map(|(start, end)| {
+                let durations: Vec<f64> = self
+                    .beats
+                    .iter()
+                    .filter(|beat| beat.start >= start && beat.start < end && beat.duration > 0.0)
+                    .map(|beat| beat.duration)
+                    .collect();
+
+                if durations.is_empty() {
+                    f64::NAN
+                } else {
+                    60.0 * durations.len() as f64 / durations.iter().sum::<f64>()
+                }
+            })
+            .collect()
+    }
+
+    pub fn chroma(&self, bins: usize) -> Vec<[f64; 12]> {
+        let duration = self.duration();
+        if bins == 0 || duration <= 0.0 {
+            return Vec::new();
+        }
+
+        let width = duration / bins as f64;
+        (0..bins)
+            .map(|bin| {
+                let (start, end) = (bin as f64 * width, (bin + 1) as f64 * width);
+
+                let mut chroma = [0.0; 12];
+                let mut total = 0.0;
+                for segment in &self.segments {
+                    let weight = overlap(segment.start, segment.end(), start, end);
+                    if weight <= 0.0 {
+                        continue;
+                    }
+
+                    for (value, pitch) in chroma.iter_mut().zip(&segment.pitches) {
+                        *value += pitch * weight;
+                    }
+                    total += weight;
+                }
+
+                if total > 0.0 {
+                    for value in chroma.iter_mut() {
+                        *value /= total;
+                    }
+                }
+
+                chroma
+            })
+            .collect()
+    }
+
+    pub fn chroma_similarity(&self, other: &AudioAnalysis, bins: usize) -> f64 {
+        let similarities: Vec<f64> = self
+            .chroma(bins)
+            .iter()
+            .zip(other.chroma(bins).iter())
+            .map(|(a, b)| cosine_similarity(a, b))
+            .collect();
+
+        if similarities.is_empty() {
+            0.0
+        } else {
+            similarities.iter().sum::<f64>() / similarities.len() as f64
+        }
+    }
+
+    fn windows(&self, window_ms: u32) -> impl Iterator<Item = (f64, f64)> {
+        let width = f64::from(window_ms.max(1)) / 1000.0;
+        let count = (self.duration() / width).ceil() as usize;
+
+        (0..count).map(move |i| (i as f64 * width, (i + 1) as f64 * width))
+    }
+}
+
+fn interval_at<T, F>(items: &[T], ms: u32, bounds: F) -> Option<(usize, &T)>
+where
+    F: Fn(&T) -> (f64, f64),
+{
+    let seconds = f64::from(ms) / 1000.0;
+    let index = items
+        .partition_point(|item| bounds(item).0 <= seconds)
+        .checked_sub(1)?;
+
+    let (start, duration) = bounds(&items[index]);
+    if seconds < start + duration {
+        Some((index, &items[index]))
+    } else {
+        None
+    }
+}
+
+fn overlap(start: f64, end: f64, window_start: f64, window_end: f64) -> f64 {
+    (end.min(window_end) - start.max(window_start)).max(0.0)
+}
+
+fn weighted_mean<I: Iterator<Item = (f64, f64)>>(values: I) -> Option<f64> {
+    let (sum, total) = values
+        .filter(|(weight, _)| *weight > 0.0)
+        .fold((0.0, 0.0), |(sum, total), (weight, value)| {
+            (sum + weight * value, total + weight)
+        });
+
+    if total > 0.0 {
+        Some(sum / total)
+    } else {
+        None
+    }
+}
+
+fn cosine_similarity(a: &[f64; 12], b: &[f64; 12]) -> f64 {
+    let dot: f64 = a.iter().zip(b.iter()).map(|(a, b)| a * b).sum();
+    let norm = |v: &[f64; 12]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+    let norms = norm(a) * norm(b);
+    if norms > 0.0 {
+        dot / norms
+    } else {
+        0.0
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct AnalysisMeta {
+    pub analyzer_version: Option<String>,
+    pub platform: Option<String>,
+    pub detailed_status: Option<String>,
+    pub status_code: Option<i32>,
+    pub timestamp: Option<i64>,
+    pub analysis_time: Option<f64>,
+    pub input_process: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct AnalysisTrack {
+    pub num_samples: Option<u64>,
+    pub duration: f64,
+    pub sample_md5: Option<String>,
+    pub offset_seconds: Option<u32>,
+    pub window_seconds: Option<u32>,
+    pub analysis_sample_rate: Option<u32>,
+    pub analysis_channels: Option<u32>,
+    pub end_of_fade_in: f64,
+    pub start_of_fade_out: f64,
+    pub loudness: f64,
+    pub tempo: f64,
+    pub tempo_confidence: f64,
+    pub time_signature: u32,
+    pub time_signature_confidence: f64,
+    pub key: i32,
+    pub key_confidence: f64,
+    pub mode: i32,
+    pub mode_confidence: f64,
+    pub codestring: Option<String>,
+    pub code_version: Option<f64>,
+    pub echoprintstring: Option<String>,
+    pub echoprint_version: Option<f64>,
+    pub synchstring: Option<String>,
+    pub synch_version: Option<f64>,
+    pub rhythmstring: Option<String>,
+    pub rhythm_version: Option<f64>,
+}
+
+impl AnalysisTrack {
+    pub fn key_name(&self) -> Option<String> {
+        key_name(self.key, self.mode)
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct TimeInterval {
     pub start: f64,
@@ -165,23 +438,42 @@ pub struct TimeInterval {
     pub confidence: f64,
 }
 
+impl TimeInterval {
+    pub fn end(&self) -> f64 {
+        self.start + self.duration
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct Section {
     pub start: f64,
     pub duration: f64,
     pub confidence: f64,
-    pub time_interval: TimeInterval,
     pub loudness: f64,
     pub tempo: f64,
     pub tempo_confidence: f64,
-    pub key: u64,
+    pub key: i32,
     pub key_confidence: f64,
-    pub mode: u64,
+    pub mode: i32,
     pub mode_confidence: f64,
-    pub time_signature: u64,
+    pub time_signature: u32,
     pub time_signature_confidence: f64,
 }
 
+impl Section {
+    pub fn end(&self) -> f64 {
+        self.start + self.duration
+    }
+
+    pub fn key_name(&self) -> Option<String> {
+        key_name(self.key, self.mode)
+    }
+}
+
+fn key_name(key: i32, mode: i32) -> Option<String> {
+    Key::new(key, mode).map(|key| key.to_string())
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct Segment {
     pub start: f64,
@@ -195,10 +487,16 @@ pub struct Segment {
     pub timbre: Vec<f64>,
 }
 
+impl Segment {
+    pub fn end(&self) -> f64 {
+        self.start + self.duration
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct AudioFeature {
     pub duration_ms: u32,
-    pub key: u32,
+    pub key: i32,
     pub mode: u32,
     pub time_signature: u32,
     pub acousticness: f32,
@@ -220,7 +518,7 @@ pub struct AudioFeature {
 
 #[derive(Clone, Debug, Default, Deserialize)]
 struct GetAudioFeaturesResponse {
-    audio_features: Vec<AudioFeature>,
+    audio_features: Vec<Option<AudioFeature>>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -3,6 +3,7 @@ use std::error::Error;
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    id::UserId,
     object::{Follower, Image},
     RequestClient,
 };
@@ -35,7 +36,7 @@ impl UserClient {
         }
     }
 
-    pub async fn get_current_user(&mut self) -> Result<User, Box<dyn Error>> {
+    pub async fn get_current_user(&self) -> Result<User, Box<dyn Error>> {
         let builder = reqwest::Client::new().get("https://api.spotify.com/v1/me");
 
         let response = self.client.send(builder).await?.unwrap();
@@ -43,7 +44,7 @@ impl UserClient {
         Ok(response.json().await?)
     }
 
-    pub async fn get_user(&mut self, id: &str) -> Result<User, Box<dyn Error>> {
+    pub async fn get_user(&self, id: &UserId) -> Result<User, Box<dyn Error>> {
         let url = format!("https://api.spotify.com/v1/users/{}", id);
         let builder = reqwest::Client::new().get(&url);
 
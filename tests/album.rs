@@ -11,10 +11,10 @@ mod album {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = AlbumClient::new(&access_token, &refresh_token);
+        let client = AlbumClient::new(&access_token, &refresh_token);
         let request = GetAlbumRequest {
-            id: "0sNOF9WDwhWunNAHPD3Baj".to_string(),
-            ..Default::default()
+            id: "0sNOF9WDwhWunNAHPD3Baj".parse().unwrap(),
+            market: None,
         };
         let album = client.get_album(request).await;
 
@@ -28,14 +28,14 @@ mod album {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = AlbumClient::new(&access_token, &refresh_token);
+        let client = AlbumClient::new(&access_token, &refresh_token);
         let ids = vec![
             "41MnTivkwTO3UUJ8DrqEJJ",
             "6JWc4iAiJ9FjyK0B59ABb4",
             "6UXCm6bOO4gFlDQZV5yL37",
         ]
         .into_iter()
-        .map(|s| s.to_string())
+        .map(|s| s.parse().unwrap())
         .collect();
         let request = GetAlbumListRequest {
             ids,
@@ -53,11 +53,12 @@ mod album {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = AlbumClient::new(&access_token, &refresh_token);
+        let client = AlbumClient::new(&access_token, &refresh_token);
         let request = GetTrackListRequest {
-            id: "6akEvsycLGftJxYudPjmqK".to_string(),
+            id: "6akEvsycLGftJxYudPjmqK".parse().unwrap(),
             limit: Some(2),
-            ..Default::default()
+            offset: None,
+            market: None,
         };
         let tracks = client.get_tracks(request).await.unwrap().get_items();
 
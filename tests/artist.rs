@@ -1,7 +1,36 @@
 extern crate spotify_api;
 #[cfg(test)]
 mod artist {
-    use spotify_api::artist::*;
+    use spotify_api::{album::SimpleAlbum, artist::*};
+
+    fn album(id: &str, name: &str, release_date: &str, markets: usize) -> SimpleAlbum {
+        SimpleAlbum {
+            id: id.to_string(),
+            name: name.to_string(),
+            release_date: Some(release_date.to_string()),
+            total_tracks: Some(10),
+            available_markets: Some(vec!["JP".to_string(); markets]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn collapse_discography() {
+        let discography = Discography::collapse(vec![
+            album("a", "Abbey Road", "1969-09-26", 1),
+            album("b", "Abbey  Road!", "1969-09-26", 5),
+            album("c", "Abbey Road (Remastered)", "2009-09-09", 1),
+            album("d", "Let It Be", "1970-05-08", 1),
+        ]);
+
+        assert_eq!(3, discography.releases.len());
+        assert_eq!("b", discography.releases[0].album.id);
+        assert_eq!("a", discography.releases[0].duplicates[0].id);
+        assert_eq!(
+            "abbey road remastered",
+            normalize_album_name("Abbey Road (Remastered)")
+        );
+    }
 
     #[tokio::test]
     async fn get_artist() {
@@ -10,9 +39,9 @@ mod artist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = ArtistClient::new(&access_token, &refresh_token);
+        let client = ArtistClient::new(&access_token, &refresh_token);
         let request = GetArtistRequest {
-            id: "0OdUWJ0sBjDrqHygGUXeCF".to_string(),
+            id: "0OdUWJ0sBjDrqHygGUXeCF".parse().unwrap(),
         };
         let artist = client.get_artist(request).await;
 
@@ -26,10 +55,10 @@ mod artist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = ArtistClient::new(&access_token, &refresh_token);
+        let client = ArtistClient::new(&access_token, &refresh_token);
         let ids = vec!["0oSGxfWSnnOXhD2fKuz2Gy", "3dBVyJ7JuOMt4GE9607Qin"]
             .into_iter()
-            .map(|s| s.to_string())
+            .map(|s| s.parse().unwrap())
             .collect();
         let request = GetArtistListRequest { ids };
         let response = client.get_artists(request).await.unwrap();
@@ -44,11 +73,13 @@ mod artist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = ArtistClient::new(&access_token, &refresh_token);
+        let client = ArtistClient::new(&access_token, &refresh_token);
         let request = GetArtistAlbumRequest {
-            id: "1vCWHaC5f2uS3yhpwWbIA6".to_string(),
+            id: "1vCWHaC5f2uS3yhpwWbIA6".parse().unwrap(),
             limit: Some(2),
-            ..Default::default()
+            include_groups: None,
+            country: None,
+            offset: None,
         };
         let albums = client.get_albums(request).await.unwrap().get_items();
 
@@ -62,10 +93,10 @@ mod artist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = ArtistClient::new(&access_token, &refresh_token);
+        let client = ArtistClient::new(&access_token, &refresh_token);
         let request = GetArtistTopTrackRequest {
-            id: "43ZHCT0cAZBISjO8DG9PnE".to_string(),
-            ..Default::default()
+            id: "43ZHCT0cAZBISjO8DG9PnE".parse().unwrap(),
+            country: None,
         };
         let _ = client.get_top_tracks(request).await.unwrap();
     }
@@ -77,10 +108,32 @@ mod artist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = ArtistClient::new(&access_token, &refresh_token);
+        let client = ArtistClient::new(&access_token, &refresh_token);
         let request = GetRelatedArtistRequest {
-            id: "43ZHCT0cAZBISjO8DG9PnE".to_string(),
+            id: "43ZHCT0cAZBISjO8DG9PnE".parse().unwrap(),
         };
         let _ = client.get_related_artists(request).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn get_discography() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = ArtistClient::new(&access_token, &refresh_token);
+        let request = GetDiscographyRequest {
+            id: "0OdUWJ0sBjDrqHygGUXeCF".parse().unwrap(),
+            include_groups: Some(vec![IncludeGroup::Album, IncludeGroup::Single]),
+            hydrate: true,
+            country: None,
+        };
+        let discography = client.get_discography(request).await.unwrap();
+
+        assert!(discography
+            .releases
+            .iter()
+            .all(|release| release.full.is_some()));
+    }
 }
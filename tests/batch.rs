@@ -0,0 +1,73 @@
+extern crate spotify_api;
+
+#[cfg(test)]
+mod batch {
+    use std::error::Error;
+
+    use spotify_api::batch::*;
+
+    #[tokio::test]
+    async fn preserves_order() {
+        let ids: Vec<u32> = (0..250).collect();
+        let result = run(
+            Endpoint::AudioFeatures,
+            ids.clone(),
+            |_, chunk| async move { Ok(chunk) },
+        )
+        .await;
+
+        assert_eq!(3, result.chunks.len());
+        assert_eq!(ids, result.into_items().unwrap());
+    }
+
+    #[tokio::test]
+    async fn respects_endpoint_limit() {
+        let ids: Vec<u32> = (0..45).collect();
+        let result = run(Endpoint::Albums, ids, |offset, chunk| async move {
+            Ok((offset, chunk.len()))
+        })
+        .await;
+
+        assert_eq!(
+            vec![(0, 20), (20, 20), (40, 5)],
+            result.into_result().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_failed_chunks() {
+        let ids: Vec<u32> = (0..120).collect();
+        let result = run(Endpoint::Tracks, ids, |offset, chunk| async move {
+            if offset == 50 {
+                Err(Box::<dyn Error>::from("rate limited"))
+            } else {
+                Ok(chunk)
+            }
+        })
+        .await;
+
+        assert!(!result.is_ok());
+
+        let failures: Vec<_> = result
+            .failures()
+            .map(|f| (f.index, f.range.clone()))
+            .collect();
+        assert_eq!(vec![(1, 50..100)], failures);
+
+        let error = result.into_items().unwrap_err();
+        assert_eq!(3, error.chunks);
+        assert_eq!(1, error.failures.len());
+    }
+
+    #[tokio::test]
+    async fn empty_input() {
+        let result = run(
+            Endpoint::Library,
+            Vec::<String>::new(),
+            |_, chunk| async move { Ok(chunk) },
+        )
+        .await;
+
+        assert!(result.into_items().unwrap().is_empty());
+    }
+}
@@ -2,7 +2,7 @@ extern crate spotify_api;
 
 #[cfg(test)]
 mod browse {
-    use spotify_api::browse::*;
+    use spotify_api::{browse::*, error::ValidationError};
 
     #[tokio::test]
     async fn get_category() {
@@ -11,7 +11,7 @@ mod browse {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = BrowseClient::new(&access_token, &refresh_token);
+        let client = BrowseClient::new(&access_token, &refresh_token);
         let request = GetCategoryRequest {
             id: "party".to_string(),
             ..Default::default()
@@ -28,7 +28,7 @@ mod browse {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = BrowseClient::new(&access_token, &refresh_token);
+        let client = BrowseClient::new(&access_token, &refresh_token);
         let request = GetCategoryPlaylistRequest {
             id: "party".to_string(),
             limit: Some(2),
@@ -51,7 +51,7 @@ mod browse {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = BrowseClient::new(&access_token, &refresh_token);
+        let client = BrowseClient::new(&access_token, &refresh_token);
         let request = GetCategoriesRequest {
             ..Default::default()
         };
@@ -65,7 +65,7 @@ mod browse {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = BrowseClient::new(&access_token, &refresh_token);
+        let client = BrowseClient::new(&access_token, &refresh_token);
         let request = GetFeaturedPlaylistRequest {
             ..Default::default()
         };
@@ -79,7 +79,7 @@ mod browse {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = BrowseClient::new(&access_token, &refresh_token);
+        let client = BrowseClient::new(&access_token, &refresh_token);
         let request = GetNewReleaseRequest {
             ..Default::default()
         };
@@ -93,9 +93,9 @@ mod browse {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = BrowseClient::new(&access_token, &refresh_token);
-        let seed_artists = vec!["4NHQUGzhtTLFvgF5SZesLK".to_string()];
-        let seed_tracks = vec!["0c6xIDDpzE81m2q797ordA".to_string()];
+        let client = BrowseClient::new(&access_token, &refresh_token);
+        let seed_artists = vec!["4NHQUGzhtTLFvgF5SZesLK".parse().unwrap()];
+        let seed_tracks = vec!["0c6xIDDpzE81m2q797ordA".parse().unwrap()];
         let min_attributes = vec![TrackAttribute::Energy(0.4), TrackAttribute::Popularity(50)];
         let request = GetRecommendationsRequest {
             seed_artists: Some(seed_artists),
@@ -103,6 +103,152 @@ mod browse {
             min_attributes: Some(min_attributes),
             ..Default::default()
         };
-        let _ = client.get_recommendations(request).await.unwrap();
+        let recommendations = client.get_recommendations(request).await.unwrap();
+
+        assert_eq!(2, recommendations.seeds.len());
+    }
+
+    #[test]
+    fn validate_recommendations() {
+        let seed_tracks = vec!["0c6xIDDpzE81m2q797ordA".parse().unwrap()];
+        let request = GetRecommendationsRequest {
+            seed_tracks: Some(seed_tracks),
+            min_attributes: Some(vec![TrackAttribute::Danceability(0.2)]),
+            max_attributes: Some(vec![TrackAttribute::Danceability(0.8)]),
+            ..Default::default()
+        };
+        assert!(request.validate().is_ok());
+
+        assert_eq!(
+            Err(ValidationError::NoSeeds),
+            GetRecommendationsRequest::default().validate()
+        );
+        assert_eq!(
+            Err(ValidationError::TooManySeeds(6)),
+            GetRecommendationsRequest {
+                seed_genres: Some(vec!["rock".to_string(); 6]),
+                ..Default::default()
+            }
+            .validate()
+        );
+        assert_eq!(
+            Err(ValidationError::AttributeOutOfRange {
+                attribute: "danceability".to_string(),
+                value: "1.5".to_string()
+            }),
+            GetRecommendationsRequest {
+                target_attributes: Some(vec![TrackAttribute::Danceability(1.5)]),
+                ..request.clone()
+            }
+            .validate()
+        );
+        assert_eq!(
+            Err(ValidationError::InvertedRange("danceability".to_string())),
+            GetRecommendationsRequest {
+                min_attributes: Some(vec![TrackAttribute::Danceability(0.9)]),
+                ..request.clone()
+            }
+            .validate()
+        );
+        assert_eq!(
+            Err(ValidationError::LimitOutOfRange(0)),
+            GetRecommendationsRequest {
+                limit: Some(0),
+                ..request
+            }
+            .validate()
+        );
+        assert_eq!("duration_ms", TrackAttribute::DurationMs(1000).to_string());
+    }
+
+    #[test]
+    fn genre_seeds() {
+        let genre_seeds = GenreSeeds {
+            genres: vec![
+                "hip-hop".to_string(),
+                "house".to_string(),
+                "j-pop".to_string(),
+                "rock".to_string(),
+                "rock-n-roll".to_string(),
+            ],
+        };
+
+        assert_eq!(Ok("hip-hop".to_string()), genre_seeds.validate("Hip Hop"));
+        assert_eq!(
+            Err(ValidationError::UnknownGenre {
+                genre: "rokc".to_string(),
+                suggestions: vec!["rock".to_string()],
+            }),
+            genre_seeds.validate("rokc")
+        );
+        assert_eq!(vec!["j-pop".to_string()], genre_seeds.suggest("jpop"));
+
+        let mut filter = recommendation::RecommendationFilter::new();
+        assert!(filter.set_checked_genre("house", &genre_seeds).is_ok());
+        assert!(filter
+            .set_checked_genre("hosue music", &genre_seeds)
+            .is_err());
+        assert_eq!(1, filter.seed.len());
+    }
+
+    #[test]
+    fn recommendation_filter_validation() {
+        use recommendation::TrackAttribute;
+
+        let mut filter = recommendation::RecommendationFilter::new();
+        for id in &["a", "b", "c", "d"] {
+            filter.set_artist(id).unwrap();
+        }
+        assert!(filter.set_track("e").is_ok());
+        assert_eq!(
+            ValidationError::TooManySeeds(6),
+            filter.set_genre("rock").unwrap_err()
+        );
+        assert_eq!(5, filter.seed.len());
+
+        assert!(filter
+            .set_min_attribute(TrackAttribute::Energy(0.5))
+            .is_ok());
+        assert!(filter
+            .set_max_attribute(TrackAttribute::Energy(1.5))
+            .is_err());
+        assert!(filter
+            .set_target_attribute(TrackAttribute::Popularity(101))
+            .is_err());
+        assert!(filter.max_attribute.is_empty());
+        assert!(filter.target_attribute.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_available_genre_seeds() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = BrowseClient::new(&access_token, &refresh_token);
+        let genre_seeds = client.get_available_genre_seeds().await.unwrap();
+
+        assert!(genre_seeds.contains("rock"));
+        assert_eq!(
+            genre_seeds.genres,
+            client.genre_seeds().await.unwrap().genres
+        );
+    }
+
+    #[test]
+    fn deserialize_recommendation_seed() {
+        let seed: RecommendationSeed = serde_json::from_value(serde_json::json!({
+            "afterFilteringSize": 250,
+            "afterRelinkingSize": 250,
+            "href": null,
+            "id": "rock",
+            "initialPoolSize": 250,
+            "type": "GENRE"
+        }))
+        .unwrap();
+
+        assert_eq!(SeedType::Genre, seed.object_type);
+        assert_eq!(250, seed.after_filtering_size);
     }
 }
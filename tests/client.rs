@@ -0,0 +1,71 @@
+extern crate spotify_api;
+
+#[cfg(test)]
+mod client {
+    use spotify_api::{
+        album::*, artist::ArtistClient, browse::BrowseClient, follow::FollowClient,
+        library::LibraryClient, personalization::PersonalizationClient, player::PlayerClient,
+        playlist::PlaylistClient, track::TrackClient, user::UserClient, RequestClient,
+    };
+
+    fn assert_shareable<T: Clone + Send + Sync>() {}
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[test]
+    fn clients_are_shareable() {
+        assert_shareable::<RequestClient>();
+        assert_shareable::<AlbumClient>();
+        assert_shareable::<ArtistClient>();
+        assert_shareable::<BrowseClient>();
+        assert_shareable::<FollowClient>();
+        assert_shareable::<LibraryClient>();
+        assert_shareable::<PersonalizationClient>();
+        assert_shareable::<PlayerClient>();
+        assert_shareable::<PlaylistClient>();
+        assert_shareable::<TrackClient>();
+        assert_shareable::<UserClient>();
+    }
+
+    #[test]
+    fn futures_are_send() {
+        let client = AlbumClient::new("access_token", "refresh_token");
+        let request = GetAlbumRequest {
+            id: "0sNOF9WDwhWunNAHPD3Baj".parse().unwrap(),
+            market: None,
+        };
+
+        assert_send(&client.get_album(request));
+    }
+
+    #[test]
+    fn clones_share_access_token() {
+        let client = RequestClient::new("access_token", "refresh_token");
+        let cloned = client.clone().set_limit(Some(1));
+
+        cloned.set_access_token("refreshed_token");
+        assert_eq!("refreshed_token", client.access_token());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn concurrent_requests() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = AlbumClient::new(&access_token, &refresh_token);
+        let requests = vec!["41MnTivkwTO3UUJ8DrqEJJ", "6JWc4iAiJ9FjyK0B59ABb4"]
+            .into_iter()
+            .map(|id| GetAlbumRequest {
+                id: id.parse().unwrap(),
+                market: None,
+            })
+            .map(|request| client.get_album(request));
+
+        let albums = futures::future::join_all(requests).await;
+
+        assert!(albums.iter().all(Result::is_ok));
+    }
+}
@@ -2,7 +2,49 @@ extern crate spotify_api;
 
 #[cfg(test)]
 mod follow {
-    use spotify_api::follow::*;
+    use futures::StreamExt;
+    use spotify_api::{artist::Artist, follow::*, playlist::SimplePlaylist, user::User};
+
+    #[test]
+    fn bulk_request_from_artists() {
+        let artist = |id: &str| Artist {
+            id: id.to_string(),
+            ..Default::default()
+        };
+
+        let request = BulkFollowRequest::from_artists(&[
+            artist("0OdUWJ0sBjDrqHygGUXeCF"),
+            artist("3dBVyJ7JuOMt4GE9607Qin"),
+            artist("0OdUWJ0sBjDrqHygGUXeCF"),
+        ])
+        .unwrap();
+        assert_eq!(2, request.ids.len());
+
+        assert!(BulkFollowRequest::from_artists(&[artist("invalid")]).is_err());
+    }
+
+    #[test]
+    fn followed_playlists_exclude_owned() {
+        let playlist = |id: &str, owner: &str| SimplePlaylist {
+            id: id.to_string(),
+            owner: User {
+                id: owner.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let playlists = followed_playlists(
+            vec![
+                playlist("owned", "wizzler"),
+                playlist("followed", "spotify"),
+            ],
+            &"wizzler".parse().unwrap(),
+        );
+
+        assert_eq!(1, playlists.len());
+        assert_eq!("followed", playlists[0].id);
+    }
 
     #[tokio::test]
     async fn is_following() {
@@ -11,10 +53,10 @@ mod follow {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = FollowClient::new(&access_token, &refresh_token);
+        let client = FollowClient::new(&access_token, &refresh_token);
 
         let request = CheckFollowRequest {
-            ids: vec!["exampleuser01".to_string()],
+            ids: vec!["exampleuser01".parse().unwrap()],
         };
 
         let result = client.is_following_user(request).await.unwrap();
@@ -23,8 +65,8 @@ mod follow {
 
         let request = CheckFollowRequest {
             ids: vec![
-                "74ASZWbe4lXaubB36ztrGX".to_string(),
-                "08td7MxkoHQkXnWAYD8d6Q".to_string(),
+                "74ASZWbe4lXaubB36ztrGX".parse().unwrap(),
+                "08td7MxkoHQkXnWAYD8d6Q".parse().unwrap(),
             ],
         };
 
@@ -40,14 +82,14 @@ mod follow {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = FollowClient::new(&access_token, &refresh_token);
+        let client = FollowClient::new(&access_token, &refresh_token);
         let request = CheckUserFollowPlaylistRequest {
-            playlist_id: "2v3iNvBX8Ay1Gt2uXtUKUT".to_string(),
-            user_ids: vec!["possan".to_string(), "elogain".to_string()],
+            playlist_id: "2v3iNvBX8Ay1Gt2uXtUKUT".parse().unwrap(),
+            user_ids: vec!["possan".parse().unwrap(), "elogain".parse().unwrap()],
         };
         let results = client.is_users_following_playlist(request).await.unwrap();
 
-        assert!(!results.get(0).unwrap());
+        assert!(!results.first().unwrap());
         assert!(!results.get(1).unwrap());
     }
 
@@ -58,20 +100,20 @@ mod follow {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = FollowClient::new(&access_token, &refresh_token);
+        let client = FollowClient::new(&access_token, &refresh_token);
 
         let request = FollowRequest {
-            ids: vec!["exampleuser01".to_string()],
+            ids: vec!["exampleuser01".parse().unwrap()],
         };
-        let _ = client.follow_users(request).await.unwrap();
+        client.follow_users(request).await.unwrap();
 
         let request = FollowRequest {
             ids: vec![
-                "74ASZWbe4lXaubB36ztrGX".to_string(),
-                "08td7MxkoHQkXnWAYD8d6Q".to_string(),
+                "74ASZWbe4lXaubB36ztrGX".parse().unwrap(),
+                "08td7MxkoHQkXnWAYD8d6Q".parse().unwrap(),
             ],
         };
-        let _ = client.follow_artists(request).await.unwrap();
+        client.follow_artists(request).await.unwrap();
     }
 
     #[tokio::test]
@@ -81,13 +123,13 @@ mod follow {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = FollowClient::new(&access_token, &refresh_token);
+        let client = FollowClient::new(&access_token, &refresh_token);
 
         let request = FollowPlaylistRequest {
-            id: "2v3iNvBX8Ay1Gt2uXtUKUT".to_string(),
-            ..Default::default()
+            id: "2v3iNvBX8Ay1Gt2uXtUKUT".parse().unwrap(),
+            public: None,
         };
-        let _ = client.follow_playlist(request).await.unwrap();
+        client.follow_playlist(request).await.unwrap();
     }
 
     #[tokio::test]
@@ -97,7 +139,7 @@ mod follow {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = FollowClient::new(&access_token, &refresh_token);
+        let client = FollowClient::new(&access_token, &refresh_token);
         let request = GetUserFollowedArtistRequest {
             limit: Some(2),
             ..Default::default()
@@ -107,6 +149,51 @@ mod follow {
         assert_eq!(2, artists.get_items().len());
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn followed_artists_stream() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = FollowClient::new(&access_token, &refresh_token);
+        let request = GetUserFollowedArtistRequest {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let artists: Vec<_> = client
+            .followed_artists_stream(request)
+            .take(5)
+            .collect()
+            .await;
+
+        assert!(artists.iter().all(Result::is_ok));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn followed_playlists_stream() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = FollowClient::new(&access_token, &refresh_token);
+        let request = GetFollowedPlaylistsRequest {
+            user_id: "wizzler".parse().unwrap(),
+            limit: Some(2),
+            offset: None,
+        };
+        let playlists: Vec<_> = client
+            .followed_playlists_stream(request)
+            .take(5)
+            .collect()
+            .await;
+
+        assert!(playlists.iter().all(Result::is_ok));
+    }
+
     #[tokio::test]
     async fn unfollow() {
         dotenv::dotenv().ok();
@@ -114,20 +201,20 @@ mod follow {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = FollowClient::new(&access_token, &refresh_token);
+        let client = FollowClient::new(&access_token, &refresh_token);
 
         let request = UnfollowRequest {
-            ids: vec!["exampleuser01".to_string()],
+            ids: vec!["exampleuser01".parse().unwrap()],
         };
-        let _ = client.unfollow_users(request).await.unwrap();
+        client.unfollow_users(request).await.unwrap();
 
         let request = UnfollowRequest {
             ids: vec![
-                "74ASZWbe4lXaubB36ztrGX".to_string(),
-                "08td7MxkoHQkXnWAYD8d6Q".to_string(),
+                "74ASZWbe4lXaubB36ztrGX".parse().unwrap(),
+                "08td7MxkoHQkXnWAYD8d6Q".parse().unwrap(),
             ],
         };
-        let _ = client.unfollow_artists(request).await.unwrap();
+        client.unfollow_artists(request).await.unwrap();
     }
 
     #[tokio::test]
@@ -137,12 +224,11 @@ mod follow {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = FollowClient::new(&access_token, &refresh_token);
+        let client = FollowClient::new(&access_token, &refresh_token);
 
         let request = UnfollowPlaylistRequest {
-            id: "2v3iNvBX8Ay1Gt2uXtUKUT".to_string(),
-            ..Default::default()
+            id: "2v3iNvBX8Ay1Gt2uXtUKUT".parse().unwrap(),
         };
-        let _ = client.unfollow_playlist(request).await.unwrap();
+        client.unfollow_playlist(request).await.unwrap();
     }
 }
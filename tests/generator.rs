@@ -0,0 +1,178 @@
+extern crate spotify_api;
+
+#[cfg(test)]
+mod generator {
+    use spotify_api::{
+        browse::TrackAttribute, error::ValidationError, generator::*, personalization::TimeRange,
+        track::Track,
+    };
+
+    fn track(id: &str) -> Track {
+        Track {
+            id: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn ids(tracks: &[Track]) -> Vec<&str> {
+        tracks.iter().map(|track| track.id.as_str()).collect()
+    }
+
+    #[test]
+    fn seeds_len() {
+        let seeds = Seeds {
+            artists: vec!["4NHQUGzhtTLFvgF5SZesLK".parse().unwrap()],
+            genres: vec!["rock".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(2, seeds.len());
+        assert!(Seeds::default().is_empty());
+    }
+
+    #[test]
+    fn seeds_dedup_and_validate() {
+        let artist = "4NHQUGzhtTLFvgF5SZesLK";
+        let mut seeds = Seeds {
+            artists: vec![artist.parse().unwrap(), artist.parse().unwrap()],
+            genres: vec!["rock", "pop", "rock", "jazz"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            tracks: vec![
+                "1301WleyT98MSxVHPZCA6M".parse().unwrap(),
+                "4iV5W9uYEdYUVa79Axb7Rh".parse().unwrap(),
+            ],
+        };
+
+        seeds.dedup();
+        assert_eq!(6, seeds.len());
+        assert_eq!(1, seeds.artists.len());
+        assert_eq!(vec!["rock", "pop", "jazz"], seeds.genres);
+        assert_eq!(Err(ValidationError::TooManySeeds(6)), seeds.validate());
+
+        seeds.genres.pop();
+        assert_eq!(Ok(()), seeds.validate());
+    }
+
+    #[tokio::test]
+    async fn resolve_seeds_rejects_too_many() {
+        let generator = PlaylistGenerator::new("", "");
+        let strategies = |genres: &[&str]| -> Vec<SeedStrategy> {
+            genres
+                .iter()
+                .map(|genre| SeedStrategy::Genre(genre.to_string()))
+                .collect()
+        };
+
+        let seeds = generator
+            .resolve_seeds(&strategies(&[
+                "rock", "pop", "rock", "jazz", "soul", "funk",
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(vec!["rock", "pop", "jazz", "soul", "funk"], seeds.genres);
+
+        let error = generator
+            .resolve_seeds(&strategies(&[
+                "rock", "pop", "rock", "jazz", "soul", "funk", "blues",
+            ]))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            Some(&ValidationError::TooManySeeds(6)),
+            error.downcast_ref::<ValidationError>()
+        );
+    }
+
+    #[test]
+    fn picker_skips_seen_and_duplicates() {
+        let mut picker = TrackPicker::new(10, vec!["seed".to_string()]);
+        picker.exclude(vec!["existing".to_string()]);
+
+        let candidates = picker.candidates(vec![
+            track("seed"),
+            track("a"),
+            track("a"),
+            track("existing"),
+            track("b"),
+        ]);
+        assert_eq!(vec!["a", "b"], ids(&candidates));
+
+        assert_eq!(1, picker.candidates(vec![track("b"), track("c")]).len());
+    }
+
+    #[test]
+    fn picker_truncates_to_size() {
+        let mut picker = TrackPicker::new(3, Vec::new());
+
+        picker.extend(vec![track("a"), track("b")]);
+        assert!(!picker.is_full());
+
+        picker.extend(vec![track("c"), track("d")]);
+        assert!(picker.is_full());
+        assert_eq!(vec!["a", "b", "c"], ids(&picker.into_tracks()));
+    }
+
+    #[tokio::test]
+    async fn generate_rejects_empty_size() {
+        let generator = PlaylistGenerator::new("", "");
+        let error = generator
+            .generate(GeneratePlaylistRequest {
+                seeds: vec![SeedStrategy::Genre("rock".to_string())],
+                user_id: "user".parse().unwrap(),
+                name: String::new(),
+                description: None,
+                public: None,
+                max_attributes: Vec::new(),
+                min_attributes: Vec::new(),
+                target_attributes: Vec::new(),
+                size: 0,
+                exclude_playlists: Vec::new(),
+                exclude_library: false,
+                market: None,
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            Some(&ValidationError::EmptyPlaylist),
+            error.downcast_ref::<ValidationError>()
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn generate() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let generator = PlaylistGenerator::new(&access_token, &refresh_token);
+        let request = GeneratePlaylistRequest {
+            user_id: "2v86jznkp2omgo6dor0y2y0yg".parse().unwrap(),
+            name: "Radio".to_string(),
+            seeds: vec![
+                SeedStrategy::TopTracks {
+                    time_range: TimeRange::ShortTerm,
+                    count: 2,
+                },
+                SeedStrategy::Genre("rock".to_string()),
+            ],
+            target_attributes: vec![TrackAttribute::Energy(0.8)],
+            size: 30,
+            exclude_library: true,
+            description: None,
+            public: None,
+            max_attributes: Vec::new(),
+            min_attributes: Vec::new(),
+            exclude_playlists: Vec::new(),
+            market: None,
+        };
+
+        let response = generator.generate(request).await.unwrap();
+
+        assert!(response.tracks.len() <= 30);
+    }
+}
@@ -0,0 +1,153 @@
+extern crate spotify_api;
+
+#[cfg(test)]
+mod graph {
+    use std::collections::HashMap;
+
+    use spotify_api::{
+        artist::{Artist, ArtistClient},
+        graph::*,
+        id::{ArtistId, Id},
+    };
+
+    fn graph() -> ArtistGraph {
+        let node = |id: &str, name: &str, depth| ArtistNode {
+            id: id.to_string(),
+            name: name.to_string(),
+            genres: vec!["rock".to_string()],
+            popularity: Some(50),
+            depth,
+        };
+        let edge = |from: &str, to: &str| ArtistEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+
+        ArtistGraph {
+            nodes: vec![
+                node("a", "The \"A\" Band", 0),
+                node("b", "B", 1),
+                node("c", "C", 1),
+            ],
+            edges: vec![edge("a", "b"), edge("a", "c"), edge("b", "c")],
+        }
+    }
+
+    #[test]
+    fn to_dot() {
+        let dot = graph().to_dot();
+
+        assert!(dot.starts_with("digraph related_artists {\n"));
+        assert!(dot.contains("\"a\" [label=\"The \\\"A\\\" Band\", depth=0, popularity=50];"));
+        assert!(dot.contains("\"b\" -> \"c\";"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let graph = graph();
+        let restored = ArtistGraph::from_json(&graph.to_json().unwrap()).unwrap();
+
+        assert_eq!(graph.nodes, restored.nodes);
+        assert_eq!(graph.edges, restored.edges);
+        assert_eq!(
+            vec!["B", "C"],
+            restored
+                .neighbours("a")
+                .iter()
+                .map(|node| node.name.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    fn artist(name: &str) -> Artist {
+        Artist {
+            id: format!("{:0>22}", name),
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    async fn explore_fake(seeds: &[&str], depth: usize, max_nodes: Option<usize>) -> ArtistGraph {
+        let related: HashMap<String, Vec<Artist>> = vec![
+            ("a", vec!["b", "c", "b"]),
+            ("b", vec!["a", "c", "d"]),
+            ("c", vec!["d"]),
+            ("d", vec!["e"]),
+        ]
+        .into_iter()
+        .map(|(from, to)| (artist(from).id, to.into_iter().map(artist).collect()))
+        .collect();
+
+        let request = ExploreRelatedRequest {
+            depth,
+            max_nodes,
+            ..Default::default()
+        };
+        let seeds = seeds.iter().map(|name| artist(name)).collect();
+
+        explore_with(seeds, &request, |id: ArtistId| {
+            let artists = related.get(id.id()).cloned().unwrap_or_default();
+            async move { Ok(artists) }
+        })
+        .await
+        .unwrap()
+    }
+
+    fn names(graph: &ArtistGraph) -> Vec<(&str, usize)> {
+        graph
+            .nodes
+            .iter()
+            .map(|node| (node.name.as_str(), node.depth))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn explore_breadth_first() {
+        let graph = explore_fake(&["a"], 1, None).await;
+        assert_eq!(vec![("a", 0), ("b", 1), ("c", 1)], names(&graph));
+        assert_eq!(2, graph.edges.len());
+
+        let graph = explore_fake(&["a"], 2, None).await;
+        assert_eq!(vec![("a", 0), ("b", 1), ("c", 1), ("d", 2)], names(&graph));
+        assert_eq!(6, graph.edges.len());
+        assert_eq!(1, graph.neighbours(&artist("c").id).len());
+
+        let graph = explore_fake(&["a", "a"], 0, None).await;
+        assert_eq!(vec![("a", 0)], names(&graph));
+    }
+
+    #[tokio::test]
+    async fn explore_caps_nodes() {
+        let graph = explore_fake(&["a", "b", "c"], 1, Some(2)).await;
+        assert_eq!(vec![("a", 0), ("b", 0)], names(&graph));
+        assert!(graph
+            .edges
+            .iter()
+            .all(|edge| graph.node(&edge.to).is_some()));
+
+        let graph = explore_fake(&["a"], 3, Some(3)).await;
+        assert_eq!(3, graph.nodes.len());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn explore_related() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = ArtistClient::new(&access_token, &refresh_token);
+        let request = ExploreRelatedRequest {
+            seeds: vec!["0OdUWJ0sBjDrqHygGUXeCF".parse().unwrap()],
+            depth: 2,
+            max_nodes: Some(50),
+            ..Default::default()
+        };
+        let graph = client.explore_related(request).await.unwrap();
+
+        assert!(graph.nodes.len() <= 50);
+        assert!(graph.nodes.iter().all(|node| node.depth <= 2));
+    }
+}
@@ -0,0 +1,120 @@
+extern crate spotify_api;
+
+#[cfg(test)]
+mod harmonic {
+    use spotify_api::{harmonic::*, playlist::diff::PlaylistDiff, track::AudioFeature};
+
+    fn feature(id: &str, key: i32, mode: u32, tempo: f32) -> AudioFeature {
+        AudioFeature {
+            id: id.to_string(),
+            uri: format!("spotify:track:{}", id),
+            key,
+            mode,
+            tempo,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn key_notation() {
+        let c_major = Key::new(0, 1).unwrap();
+        assert_eq!(c_major.to_string(), "C major");
+        assert_eq!(c_major.camelot().to_string(), "8B");
+        assert_eq!(c_major.open_key(), "1d");
+
+        let a_minor = Key::new(9, 0).unwrap();
+        assert_eq!(a_minor.camelot().to_string(), "8A");
+        assert_eq!(a_minor.open_key(), "1m");
+
+        let b_major = Key::new(11, 1).unwrap();
+        assert_eq!(b_major.camelot().to_string(), "1B");
+        assert_eq!(b_major.open_key(), "6d");
+
+        assert!(Key::new(-1, 1).is_none());
+        assert!(Key::new(12, 0).is_none());
+    }
+
+    #[test]
+    fn key_compatibility() {
+        let c_major = Key::new(0, 1).unwrap();
+
+        assert!(c_major.is_compatible(&Key::new(7, 1).unwrap()));
+        assert!(c_major.is_compatible(&Key::new(5, 1).unwrap()));
+        assert!(c_major.is_compatible(&Key::new(9, 0).unwrap()));
+        assert!(!c_major.is_compatible(&Key::new(2, 1).unwrap()));
+
+        let neighbours: Vec<String> = Key::new(11, 1)
+            .unwrap()
+            .camelot()
+            .neighbours()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(neighbours, vec!["2B", "12B", "1A"]);
+    }
+
+    #[test]
+    fn tempo_compatibility() {
+        assert!(is_tempo_compatible(128.0, 126.0, BPM_TOLERANCE));
+        assert!(is_tempo_compatible(140.0, 70.0, BPM_TOLERANCE));
+        assert!(!is_tempo_compatible(128.0, 100.0, BPM_TOLERANCE));
+    }
+
+    #[test]
+    fn compatible_track_candidates() {
+        let seed = feature("a", 0, 1, 120.0);
+        let candidates = vec![
+            seed.clone(),
+            feature("b", 7, 1, 122.0),
+            feature("c", 7, 1, 90.0),
+            feature("d", 2, 1, 120.0),
+            feature("e", 9, 0, 60.0),
+        ];
+
+        let ids: Vec<&str> = compatible_tracks(&seed, &candidates, BPM_TOLERANCE)
+            .iter()
+            .map(|feature| feature.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["b", "e"]);
+    }
+
+    #[test]
+    fn mixing_order_follows_wheel() {
+        let features = vec![
+            feature("a", 0, 1, 120.0),
+            feature("b", 2, 1, 124.0),
+            feature("c", 7, 1, 121.0),
+        ];
+
+        assert_eq!(mixing_order(&features), vec![0, 2, 1]);
+        assert!(mixing_order(&[]).is_empty());
+    }
+
+    #[test]
+    fn unknown_keys_are_appended() {
+        let current: Vec<String> = ["a", "x", "b", "local", "c", "a"]
+            .iter()
+            .map(|id| format!("spotify:track:{}", id))
+            .collect();
+        let features = vec![
+            feature("a", 0, 1, 120.0),
+            feature("x", -1, 1, 120.0),
+            feature("b", 2, 1, 124.0),
+            feature("c", 7, 1, 121.0),
+        ];
+        assert!(Key::from_feature(&features[1]).is_none());
+
+        let (uris, ordered) = harmonic_order(&current, &features);
+        let ids: Vec<&str> = uris
+            .iter()
+            .map(|uri| uri.trim_start_matches("spotify:track:"))
+            .collect();
+
+        assert_eq!(3, ordered.len());
+        assert_eq!(vec!["a", "c", "b", "x", "local", "a"], ids);
+
+        let diff = PlaylistDiff::new(&current, &uris);
+        assert!(diff.removals.is_empty() && diff.insertions.is_empty());
+        assert_eq!(uris, diff.apply(&current));
+    }
+}
@@ -0,0 +1,110 @@
+extern crate spotify_api;
+
+#[cfg(test)]
+mod history {
+    use chrono::{TimeZone, Utc};
+    use spotify_api::{
+        history::*,
+        object::{Cursor, CursorPagingObject},
+        player::PlayHistory,
+        track::SimpleTrack,
+    };
+
+    fn play(id: &str, played_at: i64) -> PlayHistory {
+        PlayHistory {
+            track: SimpleTrack {
+                id: id.to_string(),
+                uri: format!("spotify:track:{}", id),
+                ..Default::default()
+            },
+            played_at: Utc.timestamp_opt(played_at, 0).unwrap(),
+            context: None,
+        }
+    }
+
+    fn log(name: &str) -> HistoryLog {
+        let path = std::env::temp_dir().join(format!(
+            "spotify_api_history_{}_{}.ndjson",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        HistoryLog::new(path)
+    }
+
+    #[test]
+    fn append_dedupes() {
+        let log = log("append_dedupes");
+
+        assert!(log.read().unwrap().is_empty());
+        assert_eq!(None, log.last_played_at().unwrap());
+
+        let added = log
+            .append(&[play("b", 200), play("a", 100), play("a", 100)])
+            .unwrap();
+        assert_eq!(
+            vec!["a", "b"],
+            added
+                .iter()
+                .map(|play| play.track.id.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        let added = log.append(&[play("b", 200), play("c", 300)]).unwrap();
+        assert_eq!(1, added.len());
+
+        let plays = log.read().unwrap();
+        assert_eq!(3, plays.len());
+        assert_eq!(
+            Some(Utc.timestamp_opt(300, 0).unwrap()),
+            log.last_played_at().unwrap()
+        );
+
+        std::fs::remove_file(log.path()).unwrap();
+    }
+
+    #[test]
+    fn next_after_prefers_server_cursor() {
+        let appended = vec![play("a", 100), play("b", 200)];
+        let mut page = CursorPagingObject {
+            href: String::new(),
+            items: appended.clone(),
+            limit: 50,
+            next: None,
+            cursors: Some(Cursor {
+                after: Some("250000".to_string()),
+                before: None,
+            }),
+            total: None,
+        };
+
+        assert_eq!(
+            Some(Utc.timestamp_opt(250, 0).unwrap()),
+            HistoryLog::next_after(&page, &appended)
+        );
+
+        page.cursors = None;
+        assert_eq!(
+            Some(Utc.timestamp_opt(200, 0).unwrap()),
+            HistoryLog::next_after(&page, &appended)
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn sync() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = spotify_api::player::PlayerClient::new(&access_token, &refresh_token);
+        let log = log("sync");
+
+        let added = log.sync(&client).await.unwrap();
+        dbg!(added.len());
+
+        assert!(log.sync(&client).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+extern crate spotify_api;
+
+#[cfg(test)]
+mod id {
+    use spotify_api::id::*;
+
+    #[test]
+    fn parse_id() {
+        let from_id: TrackId = "4iV5W9uYEdYUVa79Axb7Rh".parse().unwrap();
+        let from_uri: TrackId = "spotify:track:4iV5W9uYEdYUVa79Axb7Rh".parse().unwrap();
+        let from_url: TrackId = "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh?si=abc"
+            .parse()
+            .unwrap();
+
+        assert_eq!(from_id, from_uri);
+        assert_eq!(from_id, from_url);
+        assert_eq!("4iV5W9uYEdYUVa79Axb7Rh", from_id.id());
+        assert_eq!("spotify:track:4iV5W9uYEdYUVa79Axb7Rh", from_id.uri());
+        assert_eq!(
+            "https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh",
+            from_id.url()
+        );
+    }
+
+    #[test]
+    fn parse_legacy_playlist_uri() {
+        let id: PlaylistId = "spotify:user:spotify:playlist:37i9dQZF1DXcBWIGoYBM5M"
+            .parse()
+            .unwrap();
+        let url: PlaylistId = "https://open.spotify.com/intl-ja/playlist/37i9dQZF1DXcBWIGoYBM5M"
+            .parse()
+            .unwrap();
+
+        assert_eq!("37i9dQZF1DXcBWIGoYBM5M", id.id());
+        assert_eq!(id, url);
+    }
+
+    #[test]
+    fn parse_user_id() {
+        let id: UserId = "spotify:user:tuggareutangranser".parse().unwrap();
+
+        assert_eq!("tuggareutangranser", id.id());
+    }
+
+    #[test]
+    fn reject_invalid() {
+        assert_eq!(Err(IdError::Empty), "".parse::<AlbumId>());
+        assert_eq!(
+            Err(IdError::InvalidId("abc".to_string())),
+            "abc".parse::<AlbumId>()
+        );
+        assert_eq!(
+            Err(IdError::WrongType {
+                expected: "album",
+                found: "track".to_string(),
+            }),
+            "spotify:track:4iV5W9uYEdYUVa79Axb7Rh".parse::<AlbumId>()
+        );
+        assert!("https://example.com/album/0sNOF9WDwhWunNAHPD3Baj"
+            .parse::<AlbumId>()
+            .is_err());
+    }
+
+    #[test]
+    fn parse_playable_id() {
+        let track: PlayableId = "4iV5W9uYEdYUVa79Axb7Rh".parse().unwrap();
+        let episode: PlayableId = "spotify:episode:512ojhOuo1ktJprKbVcKyQ".parse().unwrap();
+
+        assert_eq!("spotify:track:4iV5W9uYEdYUVa79Axb7Rh", track.uri());
+        assert_eq!("spotify:episode:512ojhOuo1ktJprKbVcKyQ", episode.uri());
+        assert!("spotify:album:0sNOF9WDwhWunNAHPD3Baj"
+            .parse::<PlayableId>()
+            .is_err());
+    }
+
+    #[test]
+    fn reject_local_items() {
+        let uri = "spotify:local:Artist:Album:Title:215";
+
+        assert!(is_local_uri(uri));
+        assert!(!is_local_uri("spotify:track:4iV5W9uYEdYUVa79Axb7Rh"));
+        assert_eq!(
+            Err(IdError::LocalItem(uri.to_string())),
+            uri.parse::<PlayableId>()
+        );
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let id: ShowId = serde_json::from_str("\"spotify:show:5AvwZVawapvyhJUIx71pdJ\"").unwrap();
+
+        assert_eq!(
+            "\"5AvwZVawapvyhJUIx71pdJ\"",
+            serde_json::to_string(&id).unwrap()
+        );
+        assert!(serde_json::from_str::<ShowId>("\"invalid\"").is_err());
+    }
+}
@@ -2,7 +2,169 @@ extern crate spotify_api;
 
 #[cfg(test)]
 mod library {
-    use spotify_api::library::*;
+    use chrono::{TimeZone, Utc};
+    use spotify_api::{error::ValidationError, id::Id, library::*};
+
+    fn item(object_type: ObjectType, id: &str, name: &str) -> LibraryItem {
+        LibraryItem {
+            object_type,
+            id: id.to_string(),
+            name: name.to_string(),
+            added_at: Some(Utc.timestamp_opt(1_600_000_000, 0).unwrap()),
+        }
+    }
+
+    fn snapshot(items: Vec<LibraryItem>) -> LibrarySnapshot {
+        LibrarySnapshot {
+            version: LibrarySnapshot::VERSION,
+            exported_at: Utc::now(),
+            items,
+        }
+    }
+
+    #[test]
+    fn snapshot_csv_round_trip() {
+        let snapshot = snapshot(vec![
+            item(
+                ObjectType::Tracks,
+                "1301WleyT98MSxVHPZCA6M",
+                "Piano, \"Live\"",
+            ),
+            item(ObjectType::Albums, "0pJJgBzj26qnE1nSQUxaB0", "Album"),
+            LibraryItem {
+                added_at: None,
+                ..item(ObjectType::Shows, "5AvwZVawapvyhJUIx71pdJ", "Show")
+            },
+        ]);
+
+        let csv = snapshot.to_csv();
+        assert!(csv.starts_with("type,id,name,added_at\n"));
+        assert!(csv.contains("\"Piano, \"\"Live\"\"\""));
+
+        let restored = LibrarySnapshot::from_csv(&csv).unwrap();
+        assert_eq!(snapshot.items, restored.items);
+        assert!(LibrarySnapshot::from_csv("id,name\n").is_err());
+    }
+
+    #[test]
+    fn snapshot_json_round_trip() {
+        let snapshot = snapshot(vec![item(
+            ObjectType::Tracks,
+            "1301WleyT98MSxVHPZCA6M",
+            "Track",
+        )]);
+
+        let restored = LibrarySnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(snapshot.items, restored.items);
+
+        let mut unsupported = snapshot;
+        unsupported.version = LibrarySnapshot::VERSION + 1;
+        let error = LibrarySnapshot::from_json(&unsupported.to_json().unwrap()).unwrap_err();
+        assert_eq!(
+            Some(&ValidationError::UnsupportedVersion {
+                document: "library snapshot",
+                version: u64::from(LibrarySnapshot::VERSION + 1),
+            }),
+            error.downcast_ref::<ValidationError>()
+        );
+    }
+
+    #[test]
+    fn snapshot_diff() {
+        let before = snapshot(vec![
+            item(ObjectType::Tracks, "1301WleyT98MSxVHPZCA6M", "Kept"),
+            item(ObjectType::Tracks, "4iV5W9uYEdYUVa79Axb7Rh", "Removed"),
+        ]);
+        let after = snapshot(vec![
+            item(ObjectType::Tracks, "1301WleyT98MSxVHPZCA6M", "Kept"),
+            item(ObjectType::Albums, "4iV5W9uYEdYUVa79Axb7Rh", "Added"),
+        ]);
+
+        let diff = before.diff(&after);
+        assert_eq!(
+            vec!["Added"],
+            diff.added
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["Removed"],
+            diff.removed
+                .iter()
+                .map(|item| item.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn index_starts_empty() {
+        let index = LibraryIndex::new(LibraryClient::new("access_token", "refresh_token"));
+
+        assert_eq!(
+            None,
+            index.contains(ObjectType::Tracks, "1301WleyT98MSxVHPZCA6M")
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn library_index() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let index = LibraryIndex::new(LibraryClient::new(&access_token, &refresh_token));
+        let id: spotify_api::id::TrackId = "1301WleyT98MSxVHPZCA6M".parse().unwrap();
+
+        index.preload(ObjectType::Tracks, None).await.unwrap();
+        assert!(index.contains(ObjectType::Tracks, id.id()).is_some());
+
+        index
+            .save_tracks(SaveRequest {
+                ids: vec![id.clone()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(Some(true), index.contains(ObjectType::Tracks, id.id()));
+        assert_eq!(
+            vec![true],
+            index
+                .is_saved_tracks(std::slice::from_ref(&id))
+                .await
+                .unwrap()
+        );
+
+        index
+            .remove_saved_tracks(RemoveSavedRequest {
+                ids: vec![id.clone()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(Some(false), index.contains(ObjectType::Tracks, id.id()));
+
+        index.invalidate();
+        assert_eq!(None, index.contains(ObjectType::Tracks, id.id()));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn export_library() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = LibraryClient::new(&access_token, &refresh_token);
+
+        let snapshot = client
+            .export_library(ExportLibraryRequest::default())
+            .await
+            .unwrap();
+        dbg!(snapshot.items.len());
+    }
 
     #[tokio::test]
     async fn is_saved() {
@@ -11,12 +173,12 @@ mod library {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = LibraryClient::new(&access_token, &refresh_token);
+        let client = LibraryClient::new(&access_token, &refresh_token);
 
         let albums_request = CheckSavedRequest {
             ids: vec![
-                "0pJJgBzj26qnE1nSQUxaB0".to_string(),
-                "5ZAKzV4ZIa5Gt7z29OYHv0".to_string(),
+                "0pJJgBzj26qnE1nSQUxaB0".parse().unwrap(),
+                "5ZAKzV4ZIa5Gt7z29OYHv0".parse().unwrap(),
             ],
         };
         let albums_results = client.is_saved_albums(albums_request).await.unwrap();
@@ -25,7 +187,7 @@ mod library {
             .for_each(|result| assert!(!result));
 
         let shows_request = CheckSavedRequest {
-            ids: vec!["5AvwZVawapvyhJUIx71pdJ".to_string()],
+            ids: vec!["5AvwZVawapvyhJUIx71pdJ".parse().unwrap()],
         };
 
         let shows_results = client.is_saved_shows(shows_request).await.unwrap();
@@ -35,8 +197,8 @@ mod library {
 
         let tracks_request = CheckSavedRequest {
             ids: vec![
-                "0udZHhCi7p1YzMlvI4fXoK".to_string(),
-                "3SF5puV5eb6bgRSxBeMOk9".to_string(),
+                "0udZHhCi7p1YzMlvI4fXoK".parse().unwrap(),
+                "3SF5puV5eb6bgRSxBeMOk9".parse().unwrap(),
             ],
         };
 
@@ -54,7 +216,7 @@ mod library {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = LibraryClient::new(&access_token, &refresh_token);
+        let client = LibraryClient::new(&access_token, &refresh_token);
 
         let albums_request = GetSavedRequest {
             limit: Some(2),
@@ -83,28 +245,28 @@ mod library {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = LibraryClient::new(&access_token, &refresh_token);
+        let client = LibraryClient::new(&access_token, &refresh_token);
 
         let albums_request = SaveRequest {
             ids: vec![
-                "0pJJgBzj26qnE1nSQUxaB0".to_string(),
-                "5ZAKzV4ZIa5Gt7z29OYHv0".to_string(),
+                "0pJJgBzj26qnE1nSQUxaB0".parse().unwrap(),
+                "5ZAKzV4ZIa5Gt7z29OYHv0".parse().unwrap(),
             ],
         };
-        let _ = client.save_albums(albums_request).await.unwrap();
+        client.save_albums(albums_request).await.unwrap();
 
         let shows_request = SaveRequest {
-            ids: vec!["5AvwZVawapvyhJUIx71pdJ".to_string()],
+            ids: vec!["5AvwZVawapvyhJUIx71pdJ".parse().unwrap()],
         };
-        let _ = client.save_shows(shows_request).await.unwrap();
+        client.save_shows(shows_request).await.unwrap();
 
         let tracks_request = SaveRequest {
             ids: vec![
-                "0udZHhCi7p1YzMlvI4fXoK".to_string(),
-                "3SF5puV5eb6bgRSxBeMOk9".to_string(),
+                "0udZHhCi7p1YzMlvI4fXoK".parse().unwrap(),
+                "3SF5puV5eb6bgRSxBeMOk9".parse().unwrap(),
             ],
         };
-        let _ = client.save_tracks(tracks_request).await.unwrap();
+        client.save_tracks(tracks_request).await.unwrap();
     }
 
     #[tokio::test]
@@ -115,27 +277,27 @@ mod library {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = LibraryClient::new(&access_token, &refresh_token);
+        let client = LibraryClient::new(&access_token, &refresh_token);
 
         let albums_request = RemoveSavedRequest {
             ids: vec![
-                "0pJJgBzj26qnE1nSQUxaB0".to_string(),
-                "5ZAKzV4ZIa5Gt7z29OYHv0".to_string(),
+                "0pJJgBzj26qnE1nSQUxaB0".parse().unwrap(),
+                "5ZAKzV4ZIa5Gt7z29OYHv0".parse().unwrap(),
             ],
         };
-        let _ = client.remove_saved_albums(albums_request).await.unwrap();
+        client.remove_saved_albums(albums_request).await.unwrap();
 
         let shows_request = RemoveSavedRequest {
-            ids: vec!["5AvwZVawapvyhJUIx71pdJ".to_string()],
+            ids: vec!["5AvwZVawapvyhJUIx71pdJ".parse().unwrap()],
         };
-        let _ = client.remove_saved_shows(shows_request).await.unwrap();
+        client.remove_saved_shows(shows_request).await.unwrap();
 
         let tracks_request = RemoveSavedRequest {
             ids: vec![
-                "0udZHhCi7p1YzMlvI4fXoK".to_string(),
-                "3SF5puV5eb6bgRSxBeMOk9".to_string(),
+                "0udZHhCi7p1YzMlvI4fXoK".parse().unwrap(),
+                "3SF5puV5eb6bgRSxBeMOk9".parse().unwrap(),
             ],
         };
-        let _ = client.remove_saved_tracks(tracks_request).await.unwrap();
+        client.remove_saved_tracks(tracks_request).await.unwrap();
     }
 }
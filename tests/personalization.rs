@@ -2,7 +2,7 @@ extern crate spotify_api;
 
 #[cfg(test)]
 mod personalization {
-    use spotify_api::personalization::*;
+    use spotify_api::{error::ValidationError, personalization::*};
 
     #[tokio::test]
     async fn get_top() {
@@ -11,7 +11,7 @@ mod personalization {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PersonalizationClient::new(&access_token, &refresh_token);
+        let client = PersonalizationClient::new(&access_token, &refresh_token);
 
         let artists_request = GetTopRequest {
             limit: Some(2),
@@ -25,4 +25,92 @@ mod personalization {
         };
         let _ = client.get_top_tracks(tracks_request).await.unwrap();
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn get_top_report() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = PersonalizationClient::new(&access_token, &refresh_token);
+
+        let report = client.get_top_report().await.unwrap();
+        assert_eq!(report.artists.len(), TimeRange::ALL.len());
+        assert_eq!(report.tracks.len(), TimeRange::ALL.len());
+    }
+
+    fn ranked(ids: &[&str]) -> Vec<RankedItem> {
+        RankedItem::rank(ids.iter().map(|id| (id.to_string(), id.to_uppercase())))
+    }
+
+    #[test]
+    fn rank_comparison() {
+        let comparison =
+            RankComparison::new(&ranked(&["a", "b", "c", "d"]), &ranked(&["c", "a", "e"]));
+
+        let new_entries: Vec<&str> = comparison
+            .new_entries
+            .iter()
+            .map(|i| i.id.as_str())
+            .collect();
+        assert_eq!(new_entries, vec!["e"]);
+
+        let dropped: Vec<&str> = comparison.dropped.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(dropped, vec!["b", "d"]);
+
+        let climbers = comparison.climbers();
+        assert_eq!(climbers.len(), 1);
+        assert_eq!((climbers[0].id.as_str(), climbers[0].delta()), ("c", 2));
+
+        let fallers = comparison.fallers();
+        assert_eq!((fallers[0].id.as_str(), fallers[0].delta()), ("a", -1));
+    }
+
+    #[test]
+    fn top_report_snapshots() {
+        let mut previous = TopReport {
+            version: TopReport::VERSION,
+            ..Default::default()
+        };
+        previous
+            .artists
+            .insert(TimeRange::ShortTerm, ranked(&["a", "b"]));
+        previous
+            .artists
+            .insert(TimeRange::LongTerm, ranked(&["b", "c"]));
+
+        let json = previous.to_json().unwrap();
+        assert!(json.contains("short_term"));
+        let previous = TopReport::from_json(&json).unwrap();
+
+        let comparison = previous.compare_ranges(
+            ObjectType::Artists,
+            TimeRange::LongTerm,
+            TimeRange::ShortTerm,
+        );
+        assert_eq!(comparison.new_entries[0].id, "a");
+        assert_eq!(comparison.dropped[0].id, "c");
+
+        let mut current = previous.clone();
+        current
+            .artists
+            .insert(TimeRange::ShortTerm, ranked(&["b", "a"]));
+        let comparison = current.compare(&previous, ObjectType::Artists, TimeRange::ShortTerm);
+        assert_eq!(comparison.climbers()[0].id, "b");
+        assert!(current
+            .compare(&previous, ObjectType::Tracks, TimeRange::ShortTerm)
+            .moves
+            .is_empty());
+
+        let error = TopReport::from_json(r#"{"version": 2}"#).unwrap_err();
+        assert_eq!(
+            Some(&ValidationError::UnsupportedVersion {
+                document: "top report",
+                version: 2,
+            }),
+            error.downcast_ref::<ValidationError>()
+        );
+    }
 }
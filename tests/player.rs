@@ -2,7 +2,360 @@ extern crate spotify_api;
 
 #[cfg(test)]
 mod player {
-    use spotify_api::player::*;
+    use std::time::Duration;
+
+    use spotify_api::{
+        error::{PlayerError, ValidationError},
+        player::*,
+    };
+
+    fn context(uri: &str, progress_ms: u32, is_playing: bool) -> CurrentlyPlayingContext {
+        serde_json::from_value(serde_json::json!({
+            "device": {
+                "id": "device",
+                "is_active": true,
+                "is_private_session": false,
+                "is_restricted": false,
+                "name": "Speaker",
+                "type": "Speaker",
+                "volume_percent": 50
+            },
+            "repeat_state": "off",
+            "shuffle_state": false,
+            "context": null,
+            "timestamp": 0,
+            "progress_ms": progress_ms,
+            "is_playing": is_playing,
+            "item": { "uri": uri, "duration_ms": 200_000 },
+            "currently_playing_type": "track",
+            "actions": {}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn playback_events() {
+        let track = "spotify:track:1301WleyT98MSxVHPZCA6M";
+        let previous = context(track, 10_000, true);
+
+        assert_eq!(
+            Vec::<PlaybackEventKind>::new(),
+            PlaybackEventKind::between(
+                Some(&previous),
+                Some(&context(track, 12_000, true)),
+                Duration::from_secs(2)
+            )
+        );
+        assert_eq!(
+            vec![PlaybackEventKind::TrackChanged],
+            PlaybackEventKind::between(
+                Some(&previous),
+                Some(&context("spotify:track:4iV5W9uYEdYUVa79Axb7Rh", 0, true)),
+                Duration::from_secs(2)
+            )
+        );
+        assert_eq!(
+            vec![PlaybackEventKind::Paused],
+            PlaybackEventKind::between(
+                Some(&previous),
+                Some(&context(track, 11_000, false)),
+                Duration::from_secs(2)
+            )
+        );
+        assert_eq!(
+            vec![PlaybackEventKind::Seeked {
+                from_ms: 12_000,
+                to_ms: 90_000
+            }],
+            PlaybackEventKind::between(
+                Some(&previous),
+                Some(&context(track, 90_000, true)),
+                Duration::from_secs(2)
+            )
+        );
+        assert_eq!(
+            vec![PlaybackEventKind::Stopped],
+            PlaybackEventKind::between(Some(&previous), None, Duration::from_secs(2))
+        );
+    }
+
+    #[test]
+    fn playback_device_events() {
+        let track = "spotify:track:1301WleyT98MSxVHPZCA6M";
+        let previous = context(track, 10_000, false);
+
+        let mut current = previous.clone();
+        current.device.volume_percent = Some(80);
+        current.shuffle_state = true;
+        current.repeat_state = RepeatState::Context;
+
+        assert_eq!(
+            vec![
+                PlaybackEventKind::VolumeChanged {
+                    from: Some(50),
+                    to: Some(80)
+                },
+                PlaybackEventKind::ShuffleToggled(true),
+                PlaybackEventKind::RepeatChanged(RepeatState::Context),
+            ],
+            PlaybackEventKind::between(Some(&previous), Some(&current), Duration::from_secs(5))
+        );
+
+        current.device.id = Some("other".to_string());
+        assert_eq!(
+            Some(&PlaybackEventKind::DeviceChanged),
+            PlaybackEventKind::between(Some(&previous), Some(&current), Duration::from_secs(5))
+                .first()
+        );
+    }
+
+    fn device(id: &str, name: &str, device_type: &str, is_active: bool) -> Device {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "is_active": is_active,
+            "is_private_session": false,
+            "is_restricted": false,
+            "name": name,
+            "type": device_type,
+            "volume_percent": 50
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn select_device() {
+        let devices = vec![
+            device("computer", "Laptop", "Computer", false),
+            device("speaker", "Kitchen", "Speaker", true),
+            device("phone", "Phone", "Smartphone", false),
+        ];
+
+        let select = |selector: DeviceSelector, last_active: Option<&str>| {
+            selector
+                .select(&devices, last_active)
+                .and_then(|device| device.id.clone())
+        };
+
+        assert_eq!(
+            Some("computer".to_string()),
+            select(DeviceSelector::Name("Laptop".to_string()), None)
+        );
+        assert_eq!(
+            Some("phone".to_string()),
+            select(DeviceSelector::Type(DeviceType::Smartphone), None)
+        );
+        assert_eq!(
+            Some("speaker".to_string()),
+            select(DeviceSelector::Active, None)
+        );
+        assert_eq!(None, select(DeviceSelector::Type(DeviceType::TV), None));
+
+        let inactive: Vec<Device> = devices
+            .iter()
+            .cloned()
+            .map(|mut device| {
+                device.is_active = false;
+                device
+            })
+            .collect();
+
+        assert!(DeviceSelector::Active.select(&inactive, None).is_none());
+        assert_eq!(
+            Some("phone"),
+            DeviceSelector::MostRecentlyActive
+                .select(&inactive, Some("phone"))
+                .and_then(|device| device.id.as_deref())
+        );
+    }
+
+    #[test]
+    fn player_error_from_reason() {
+        assert_eq!(
+            PlayerError::NoActiveDevice,
+            PlayerError::from_reason("NO_ACTIVE_DEVICE", "Player command failed")
+        );
+        assert_eq!(
+            PlayerError::PremiumRequired,
+            PlayerError::from_reason("PREMIUM_REQUIRED", "Player command failed")
+        );
+        assert_eq!(
+            PlayerError::Restricted {
+                reason: "CONTEXT_DISALLOW".to_string(),
+                message: "Player command failed: Restriction violated".to_string(),
+            },
+            PlayerError::from_reason(
+                "CONTEXT_DISALLOW",
+                "Player command failed: Restriction violated"
+            )
+        );
+    }
+
+    #[test]
+    fn validate_requests() {
+        assert!(SetVolumeRequest::new(100).is_ok());
+        assert_eq!(
+            ValidationError::VolumeOutOfRange(101),
+            SetVolumeRequest::new(101).unwrap_err()
+        );
+        assert_eq!(
+            ValidationError::PositionOutOfRange {
+                position_ms: 250_000,
+                duration_ms: 200_000
+            },
+            SeekRequest::new(250_000, 200_000).unwrap_err()
+        );
+
+        assert_eq!(
+            &StartTarget::Context("spotify:playlist:37i9dQZF1DXcBWIGoYBM5M".to_string()),
+            StartRequest::context("https://open.spotify.com/playlist/37i9dQZF1DXcBWIGoYBM5M")
+                .unwrap()
+                .target()
+        );
+        assert!(StartRequest::context("37i9dQZF1DXcBWIGoYBM5M").is_err());
+        assert!(StartRequest::context("spotify:track:1301WleyT98MSxVHPZCA6M").is_err());
+        assert_eq!(
+            ValidationError::EmptyUris,
+            StartRequest::uris(Vec::new()).unwrap_err()
+        );
+
+        let uris = vec!["spotify:track:1301WleyT98MSxVHPZCA6M".parse().unwrap()];
+        assert!(StartRequest::uris(uris.clone())
+            .unwrap()
+            .set_offset(Offset::Position(0))
+            .is_ok());
+        assert_eq!(
+            ValidationError::OffsetOutOfRange {
+                position: 1,
+                len: 1
+            },
+            StartRequest::uris(uris.clone())
+                .unwrap()
+                .set_offset(Offset::Position(1))
+                .unwrap_err()
+        );
+        assert!(StartRequest::uris(uris)
+            .unwrap()
+            .set_offset(Offset::Uri(
+                "spotify:track:4iV5W9uYEdYUVa79Axb7Rh".parse().unwrap()
+            ))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn transfer_rejects_active_device() {
+        let client = PlayerClient::new("", "");
+        let error = client
+            .transfer_playlback(TransferPlaybackRequest {
+                device: DeviceSelector::Active,
+                play: None,
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            Some(&ValidationError::TransferToActiveDevice),
+            error.downcast_ref::<ValidationError>()
+        );
+    }
+
+    #[test]
+    fn get_currently_query() {
+        let request = GetCurrentlyRequest {
+            additional_types: Some(vec![ObjectType::Track, ObjectType::Episode]),
+            ..Default::default()
+        };
+
+        let built = reqwest::Client::new()
+            .get("https://api.spotify.com/v1/me/player")
+            .query(&request.query())
+            .build()
+            .unwrap();
+        assert_eq!(
+            built.url().query(),
+            Some("additional_types=track%2Cepisode")
+        );
+
+        assert!(GetCurrentlyRequest::default().query().is_empty());
+    }
+
+    #[test]
+    fn deserialize_queue() {
+        let queue: Queue = serde_json::from_value(serde_json::json!({
+            "currently_playing": null,
+            "queue": [
+                {
+                    "artists": [],
+                    "disc_number": 1,
+                    "duration_ms": 200_000,
+                    "explicit": false,
+                    "href": "https://api.spotify.com/v1/tracks/1301WleyT98MSxVHPZCA6M",
+                    "id": "1301WleyT98MSxVHPZCA6M",
+                    "name": "Track",
+                    "track_number": 1,
+                    "type": "track",
+                    "uri": "spotify:track:1301WleyT98MSxVHPZCA6M",
+                    "is_local": false
+                },
+                {
+                    "audio_preview_url": null,
+                    "description": "Episode",
+                    "duration_ms": 1_800_000,
+                    "explicit": false,
+                    "href": "https://api.spotify.com/v1/episodes/512ojhOuo1ktJprKbVcKyQ",
+                    "id": "512ojhOuo1ktJprKbVcKyQ",
+                    "images": [],
+                    "is_externally_hosted": false,
+                    "languages": ["en"],
+                    "name": "Episode",
+                    "release_date": "2020-01-01",
+                    "release_date_precision": "day",
+                    "type": "episode",
+                    "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert!(queue.currently_playing.is_none());
+        assert!(matches!(queue.queue[0], PlayableItem::Track(_)));
+        assert!(matches!(queue.queue[1], PlayableItem::Episode(_)));
+        assert!(queue.contains("spotify:episode:512ojhOuo1ktJprKbVcKyQ"));
+        assert!(!queue.contains("spotify:track:4iV5W9uYEdYUVa79Axb7Rh"));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn get_queue() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = PlayerClient::new(&access_token, &refresh_token);
+
+        let queue = client.get_queue().await.unwrap();
+        dbg!(&queue);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn watch_playback() {
+        use futures::StreamExt;
+
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = PlayerClient::new(&access_token, &refresh_token);
+
+        let events = client
+            .watch_playback(WatchPlaybackRequest::default())
+            .take(3)
+            .collect::<Vec<_>>()
+            .await;
+        dbg!(&events);
+    }
 
     #[tokio::test]
     #[ignore]
@@ -12,11 +365,11 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
 
         let request = AddItemRequest {
-            uri: "spotify:track:1301WleyT98MSxVHPZCA6M".to_string(),
-            ..Default::default()
+            uri: "spotify:track:1301WleyT98MSxVHPZCA6M".parse().unwrap(),
+            device: None,
         };
 
         client.add_item(request).await.unwrap();
@@ -30,7 +383,7 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
 
         let devices = client.get_devices().await.unwrap().devices;
         dbg!(&devices);
@@ -44,7 +397,7 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
         let request = GetCurrentlyRequest {
             ..Default::default()
         };
@@ -63,7 +416,7 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
         let request = GetRecentlyPlayedTracksRequest {
             limit: Some(2),
             ..Default::default()
@@ -87,7 +440,7 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
         let request = GetCurrentlyRequest {
             ..Default::default()
         };
@@ -106,7 +459,7 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
         let request = PauseRequest {
             ..Default::default()
         };
@@ -122,7 +475,7 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
 
         client.start(None).await.unwrap();
     }
@@ -135,12 +488,9 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
 
-        let request = SeekRequest {
-            position_ms: 25000,
-            ..Default::default()
-        };
+        let request = SeekRequest::new(25000, 60000).unwrap();
 
         client.seek_to_position(request).await.unwrap();
     }
@@ -153,7 +503,7 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
 
         let request = SetRepeatModeRequest {
             state: RepeatState::Context,
@@ -171,12 +521,9 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
 
-        let request = SetVolumeRequest {
-            volume_percent: 20,
-            ..Default::default()
-        };
+        let request = SetVolumeRequest::new(20).unwrap();
 
         client.set_volume(request).await.unwrap();
     }
@@ -189,7 +536,7 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
 
         let request = SkipRequest {
             ..Default::default()
@@ -206,7 +553,7 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
 
         let request = SkipRequest {
             ..Default::default()
@@ -223,12 +570,9 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
 
-        let request = StartRequest {
-            context_uri: Some("spotify:album:5ht7ItJgpBH7W6vJ5BqpPr".to_string()),
-            ..Default::default()
-        };
+        let request = StartRequest::context("spotify:album:5ht7ItJgpBH7W6vJ5BqpPr").unwrap();
 
         client.start(Some(request)).await.unwrap();
     }
@@ -241,12 +585,12 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
 
-        let request = StartRequest {
-            uris: Some(vec!["spotify:track:1301WleyT98MSxVHPZCA6M".to_string()]),
-            ..Default::default()
-        };
+        let request = StartRequest::uris(vec!["spotify:track:1301WleyT98MSxVHPZCA6M"
+            .parse()
+            .unwrap()])
+        .unwrap();
 
         client.start(Some(request)).await.unwrap();
     }
@@ -259,7 +603,7 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
         let request = ToggleShuffleRequest {
             state: true,
             ..Default::default()
@@ -276,9 +620,9 @@ mod player {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlayerClient::new(&access_token, &refresh_token);
+        let client = PlayerClient::new(&access_token, &refresh_token);
         let request = TransferPlaybackRequest {
-            device_id: "".to_string(),
+            device: DeviceSelector::MostRecentlyActive,
             play: Some(true),
         };
 
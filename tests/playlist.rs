@@ -2,7 +2,10 @@ extern crate spotify_api;
 
 #[cfg(test)]
 mod playlist {
-    use spotify_api::playlist::*;
+    use spotify_api::{
+        error::{ApiError, ValidationError},
+        playlist::*,
+    };
 
     #[tokio::test]
     // #[ignore]
@@ -12,20 +15,20 @@ mod playlist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlaylistClient::new(&access_token, &refresh_token);
+        let client = PlaylistClient::new(&access_token, &refresh_token);
 
         let mut uris = Vec::new();
         while uris.len() < 100 {
-            uris.push("spotify:track:4iV5W9uYEdYUVa79Axb7Rh".to_string());
-            uris.push("spotify:track:1301WleyT98MSxVHPZCA6M".to_string());
-            uris.push("spotify:episode:512ojhOuo1ktJprKbVcKyQ".to_string());
+            uris.push("spotify:track:4iV5W9uYEdYUVa79Axb7Rh".parse().unwrap());
+            uris.push("spotify:track:1301WleyT98MSxVHPZCA6M".parse().unwrap());
+            uris.push("spotify:episode:512ojhOuo1ktJprKbVcKyQ".parse().unwrap());
         }
         dbg!(uris.len());
 
         let request = AddItemsRequest {
-            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".to_string(),
+            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".parse().unwrap(),
             uris,
-            ..Default::default()
+            position: None,
         };
 
         let _ = client.add_items(request).await.unwrap();
@@ -39,9 +42,9 @@ mod playlist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlaylistClient::new(&access_token, &refresh_token);
+        let client = PlaylistClient::new(&access_token, &refresh_token);
         let request = ChangeDetailRequest {
-            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".to_string(),
+            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".parse().unwrap(),
             name: Some("test".to_string()),
             public: Some(true),
             collaborative: Some(true),
@@ -59,13 +62,14 @@ mod playlist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlaylistClient::new(&access_token, &refresh_token);
+        let client = PlaylistClient::new(&access_token, &refresh_token);
 
         let request = CreatePlaylistRequest {
-            user_id: "2v86jznkp2omgo6dor0y2y0yg".to_string(),
+            user_id: "2v86jznkp2omgo6dor0y2y0yg".parse().unwrap(),
             name: "test".to_string(),
             description: Some("API test playlist".to_string()),
-            ..Default::default()
+            public: None,
+            collaborative: None,
         };
 
         let playlist = client.create_playlist(request).await.unwrap();
@@ -79,7 +83,7 @@ mod playlist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlaylistClient::new(&access_token, &refresh_token);
+        let client = PlaylistClient::new(&access_token, &refresh_token);
 
         let request = GetPlaylistsRequest {
             limit: Some(2),
@@ -89,7 +93,7 @@ mod playlist {
         let _ = client.get_playlists(request).await.unwrap().get_items();
 
         let request = GetPlaylistsRequest {
-            user_id: Some("wizzler".to_string()),
+            user_id: Some("wizzler".parse().unwrap()),
             limit: Some(2),
             ..Default::default()
         };
@@ -104,11 +108,11 @@ mod playlist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlaylistClient::new(&access_token, &refresh_token);
+        let client = PlaylistClient::new(&access_token, &refresh_token);
 
         let request = GetPlaylistRequest {
-            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".to_string(),
-            ..Default::default()
+            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".parse().unwrap(),
+            market: None,
         };
 
         let _ = client.get_playlist(request).await.unwrap();
@@ -121,10 +125,10 @@ mod playlist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlaylistClient::new(&access_token, &refresh_token);
+        let client = PlaylistClient::new(&access_token, &refresh_token);
 
         let request = GetImageRequest {
-            playlist_id: "3cEYpjA9oz9GiPac4AsH4n".to_string(),
+            playlist_id: "3cEYpjA9oz9GiPac4AsH4n".parse().unwrap(),
         };
 
         let _ = client.get_image(request).await.unwrap();
@@ -137,12 +141,13 @@ mod playlist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlaylistClient::new(&access_token, &refresh_token);
+        let client = PlaylistClient::new(&access_token, &refresh_token);
 
         let request = GetPlaylistTracksRequest {
-            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".to_string(),
+            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".parse().unwrap(),
             limit: Some(2),
-            ..Default::default()
+            offset: None,
+            market: None,
         };
 
         let _ = client.get_tracks(request).await.unwrap().get_items();
@@ -156,19 +161,19 @@ mod playlist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlaylistClient::new(&access_token, &refresh_token);
+        let client = PlaylistClient::new(&access_token, &refresh_token);
 
         let tracks = vec![
-            "spotify:track:4iV5W9uYEdYUVa79Axb7Rh".to_string(),
-            "spotify:episode:512ojhOuo1ktJprKbVcKyQ".to_string(),
+            "spotify:track:4iV5W9uYEdYUVa79Axb7Rh".parse().unwrap(),
+            "spotify:episode:512ojhOuo1ktJprKbVcKyQ".parse().unwrap(),
         ];
 
         let tracks = tracks.into_iter().map(|uri| (uri, None)).collect();
 
         let request = RemoveItemsRequest {
-            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".to_string(),
+            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".parse().unwrap(),
             tracks,
-            ..Default::default()
+            snapshot_id: None,
         };
 
         let _ = client.remove_items(request).await.unwrap();
@@ -181,13 +186,14 @@ mod playlist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlaylistClient::new(&access_token, &refresh_token);
+        let client = PlaylistClient::new(&access_token, &refresh_token);
 
         let request = ReorderRequest {
-            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".to_string(),
+            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".parse().unwrap(),
             range_start: 0,
             insert_before: 2,
-            ..Default::default()
+            range_length: None,
+            snapshot_id: None,
         };
 
         let _ = client.reorder(request).await.unwrap();
@@ -200,17 +206,267 @@ mod playlist {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = PlaylistClient::new(&access_token, &refresh_token);
+        let client = PlaylistClient::new(&access_token, &refresh_token);
 
         let request = ReplaceRequest {
-            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".to_string(),
+            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".parse().unwrap(),
+            uris: vec![
+                "spotify:track:4iV5W9uYEdYUVa79Axb7Rh".parse().unwrap(),
+                "spotify:track:1301WleyT98MSxVHPZCA6M".parse().unwrap(),
+                "spotify:episode:512ojhOuo1ktJprKbVcKyQ".parse().unwrap(),
+            ],
+        };
+
+        client.replace(request).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn sync_playlist() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = PlaylistClient::new(&access_token, &refresh_token);
+
+        let request = SyncPlaylistRequest {
+            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".parse().unwrap(),
             uris: vec![
-                "spotify:track:4iV5W9uYEdYUVa79Axb7Rh".to_string(),
-                "spotify:track:1301WleyT98MSxVHPZCA6M".to_string(),
-                "spotify:episode:512ojhOuo1ktJprKbVcKyQ".to_string(),
+                "spotify:track:1301WleyT98MSxVHPZCA6M".parse().unwrap(),
+                "spotify:track:4iV5W9uYEdYUVa79Axb7Rh".parse().unwrap(),
             ],
+            retries: 2,
+        };
+
+        let response = client.sync_playlist(request).await.unwrap();
+        dbg!(&response.applied);
+    }
+
+    const BACKUP: &str = r#"{
+        "version": 1,
+        "exported_at": "2020-07-01T12:00:00Z",
+        "playlist": {
+            "collaborative": false,
+            "description": "API test playlist",
+            "followers": { "href": null, "total": 0 },
+            "href": "https://api.spotify.com/v1/playlists/3PJeEBUHSVRa1rJ6KOP30H",
+            "id": "3PJeEBUHSVRa1rJ6KOP30H",
+            "images": [],
+            "name": "test",
+            "owner": {
+                "href": "https://api.spotify.com/v1/users/wizzler",
+                "id": "wizzler",
+                "type": "user",
+                "uri": "spotify:user:wizzler"
+            },
+            "public": true,
+            "snapshot_id": "MiwzMmE3YjE5NjcxYzY",
+            "tracks": {
+                "href": "https://api.spotify.com/v1/playlists/3PJeEBUHSVRa1rJ6KOP30H/tracks",
+                "items": [],
+                "limit": 100,
+                "next": null,
+                "offset": 0,
+                "previous": null,
+                "total": 1
+            },
+            "type": "playlist",
+            "uri": "spotify:playlist:3PJeEBUHSVRa1rJ6KOP30H"
+        },
+        "tracks": [
+            {
+                "added_at": "2020-06-30T09:00:00Z",
+                "added_by": {
+                    "href": "https://api.spotify.com/v1/users/wizzler",
+                    "id": "wizzler",
+                    "type": "user",
+                    "uri": "spotify:user:wizzler"
+                },
+                "is_local": false,
+                "track": {
+                    "artists": [],
+                    "disc_number": 1,
+                    "duration_ms": 215000,
+                    "explicit": false,
+                    "href": "https://api.spotify.com/v1/tracks/4iV5W9uYEdYUVa79Axb7Rh",
+                    "id": "4iV5W9uYEdYUVa79Axb7Rh",
+                    "name": "New Noise",
+                    "track_number": 1,
+                    "type": "track",
+                    "uri": "spotify:track:4iV5W9uYEdYUVa79Axb7Rh",
+                    "is_local": false
+                }
+            }
+        ],
+        "images": [
+            { "height": 640, "url": "https://i.scdn.co/image/ab67616d0000b273", "width": 640 }
+        ]
+    }"#;
+
+    #[test]
+    fn backup_round_trip() {
+        let backup = PlaylistBackup::from_json(BACKUP).unwrap();
+        assert_eq!(1, backup.tracks.len());
+        assert_eq!("wizzler", backup.tracks[0].added_by.id);
+
+        let restored = PlaylistBackup::from_json(&backup.to_json().unwrap()).unwrap();
+        assert_eq!(backup.playlist.id, restored.playlist.id);
+        assert_eq!(backup.tracks[0].added_at, restored.tracks[0].added_at);
+        assert_eq!(1, restored.images.len());
+    }
+
+    #[test]
+    fn backup_rejects_unknown_version() {
+        let json = BACKUP.replacen("\"version\": 1", "\"version\": 99", 1);
+
+        let error = PlaylistBackup::from_json(&json).unwrap_err();
+        assert_eq!(
+            Some(&ValidationError::UnsupportedVersion {
+                document: "playlist backup",
+                version: 99,
+            }),
+            error.downcast_ref::<ValidationError>()
+        );
+    }
+
+    #[test]
+    fn restore_collaborative_playlist_as_private() {
+        let json = BACKUP.replacen("\"collaborative\": false", "\"collaborative\": true", 1);
+        let request = RestorePlaylistRequest {
+            user_id: "wizzler".parse().unwrap(),
+            name: None,
+            backup: PlaylistBackup::from_json(&json).unwrap(),
+        };
+
+        let create = request.create_request();
+        assert_eq!(Some(true), create.collaborative);
+        assert_eq!(Some(false), create.public);
+        assert_eq!(request.backup.playlist.name, create.name);
+
+        let request = RestorePlaylistRequest {
+            backup: PlaylistBackup::from_json(BACKUP).unwrap(),
+            name: Some("Restored".to_string()),
+            ..request
+        };
+
+        let create = request.create_request();
+        assert_eq!(Some(false), create.collaborative);
+        assert_eq!(Some(true), create.public);
+        assert_eq!("Restored", create.name);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn backup_playlist() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = PlaylistClient::new(&access_token, &refresh_token);
+
+        let request = BackupPlaylistRequest {
+            playlist_id: "3PJeEBUHSVRa1rJ6KOP30H".parse().unwrap(),
+            market: None,
+        };
+
+        let backup = client.backup_playlist(request).await.unwrap();
+        dbg!(backup.to_json().unwrap());
+    }
+
+    fn uris(items: &str) -> Vec<String> {
+        items
+            .split_whitespace()
+            .map(|item| format!("spotify:track:{}", item))
+            .collect()
+    }
+
+    #[test]
+    fn diff_apply() {
+        let cases = [
+            ("a b c", "a b c"),
+            ("a b c", "c b a"),
+            ("a b c d e", "b d f"),
+            ("", "a b"),
+            ("a b", ""),
+            ("a a b a", "a b a"),
+            ("a b c d", "x a y c b z"),
+            ("e d c b a", "a b c d e f"),
+        ];
+
+        for (current, desired) in cases.iter() {
+            let current = uris(current);
+            let desired = uris(desired);
+            let diff = diff::PlaylistDiff::new(&current, &desired);
+
+            assert_eq!(desired, diff.apply(&current));
+        }
+    }
+
+    #[test]
+    fn diff_is_minimal() {
+        let current = uris("a b c d e");
+
+        assert!(diff::PlaylistDiff::new(&current, &current).is_empty());
+
+        let diff = diff::PlaylistDiff::new(&current, &uris("a c d e b"));
+        assert!(diff.removals.is_empty());
+        assert!(diff.insertions.is_empty());
+        assert_eq!(1, diff.moves.len());
+
+        let diff = diff::PlaylistDiff::new(&current, &uris("a b x y c d"));
+        assert_eq!(
+            vec![diff::Removal {
+                uri: "spotify:track:e".to_string(),
+                positions: vec![4],
+            }],
+            diff.removals
+        );
+        assert!(diff.moves.is_empty());
+        assert_eq!(
+            vec![diff::Insertion {
+                position: 2,
+                uris: uris("x y"),
+            }],
+            diff.insertions
+        );
+    }
+
+    #[test]
+    fn diff_preserves_local_items() {
+        let mut current = uris("a b c");
+        current.insert(1, "spotify:local:Artist:Album:Song:180".to_string());
+
+        let desired = diff::preserve_local(&current, &uris("c a d"));
+        let diff = diff::PlaylistDiff::new(&current, &desired);
+
+        assert!(diff
+            .removals
+            .iter()
+            .all(|removal| !removal.uri.contains(":local:")));
+        assert!(diff
+            .insertions
+            .iter()
+            .all(|insertion| insertion.uris.iter().all(|uri| !uri.contains(":local:"))));
+        assert_eq!(desired, diff.apply(&current));
+        assert_eq!(4, desired.len());
+    }
+
+    #[test]
+    fn snapshot_conflicts() {
+        let status = |status: u16, message: &str| ApiError::Status {
+            status,
+            message: message.to_string(),
+            reason: None,
         };
 
-        let _ = client.replace(request).await.unwrap();
+        assert!(status(409, "Conflict").is_snapshot_conflict());
+        assert!(status(412, "Precondition failed").is_snapshot_conflict());
+        assert!(status(400, "Invalid snapshot id").is_snapshot_conflict());
+        assert!(!status(400, "Invalid track uri").is_snapshot_conflict());
+        assert!(!status(403, "Forbidden").is_snapshot_conflict());
+        assert!(!status(404, "Not found").is_snapshot_conflict());
+        assert!(!ApiError::RateLimited { retry_after: None }.is_snapshot_conflict());
     }
 }
@@ -0,0 +1,127 @@
+extern crate spotify_api;
+
+#[cfg(test)]
+mod profile {
+    use chrono::Utc;
+    use spotify_api::{
+        album::SimpleAlbum,
+        artist::SimpleArtist,
+        browse::TrackAttribute,
+        playlist::PlaylistTrack,
+        profile::*,
+        track::{AudioFeature, Track},
+    };
+
+    fn item(id: &str, artist: &str, explicit: bool, release_date: &str) -> PlaylistTrack {
+        PlaylistTrack {
+            added_at: Utc::now(),
+            added_by: Default::default(),
+            is_local: false,
+            track: Track {
+                id: id.to_string(),
+                uri: format!("spotify:track:{}", id),
+                duration_ms: 200_000,
+                explicit,
+                artists: vec![SimpleArtist {
+                    id: artist.to_lowercase(),
+                    name: artist.to_string(),
+                    ..Default::default()
+                }],
+                album: Some(SimpleAlbum {
+                    release_date: Some(release_date.to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn feature(id: &str, tempo: f32, energy: f32) -> AudioFeature {
+        AudioFeature {
+            id: id.to_string(),
+            tempo,
+            energy,
+            ..Default::default()
+        }
+    }
+
+    fn profile() -> PlaylistProfile {
+        PlaylistProfile::new(
+            vec![
+                item("a", "Alpha", true, "1999-05-01"),
+                item("b", "Beta", false, "2005"),
+                item("c", "Alpha", false, "2005-01"),
+                item("d", "Gamma", false, "2010-10-10"),
+            ],
+            vec![
+                feature("a", 121.0, 0.8),
+                feature("b", 128.5, 0.6),
+                feature("c", 95.0, 0.4),
+            ],
+        )
+    }
+
+    #[test]
+    fn playlist_profile_stats() {
+        let stats = profile().stats();
+
+        assert_eq!(stats.track_count, 4);
+        assert_eq!(stats.analyzed_count, 3);
+        assert_eq!(stats.total_duration_ms, 800_000);
+        assert_eq!(stats.explicit_ratio, 0.25);
+
+        let averages = stats.averages.unwrap();
+        assert!((averages.energy - 0.6).abs() < 1e-6);
+
+        assert_eq!(stats.tempo_histogram.get(&90), Some(&1));
+        assert_eq!(stats.tempo_histogram.get(&120), Some(&2));
+        assert_eq!(stats.release_years.get(&2005), Some(&2));
+        assert_eq!(stats.top_artists[0].name, "Alpha");
+        assert_eq!(stats.top_artists[0].count, 2);
+        assert_eq!(stats.skipped, vec!["spotify:track:d"]);
+    }
+
+    #[test]
+    fn playlist_profile_filter() {
+        let profile = profile();
+
+        let filters =
+            TrackFilter::between(TrackAttribute::Tempo(120.0), TrackAttribute::Tempo(130.0));
+        let ids: Vec<&str> = profile
+            .filter(&filters)
+            .iter()
+            .map(|track| track.item.track.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["a", "b"]);
+
+        let filters = vec![
+            TrackFilter::Explicit(false),
+            TrackFilter::ReleasedBetween(2000, 2009),
+            TrackFilter::Artist("alpha".to_string()),
+        ];
+        assert_eq!(profile.filter(&filters).len(), 1);
+    }
+
+    #[test]
+    fn empty_profile_stats() {
+        let stats = PlaylistProfile::default().stats();
+
+        assert_eq!(stats.track_count, 0);
+        assert_eq!(stats.explicit_ratio, 0.0);
+        assert!(stats.averages.is_none());
+    }
+
+    #[test]
+    fn unknown_key_is_not_an_attribute() {
+        let track = ProfiledTrack {
+            item: item("a", "Alpha", false, "2000"),
+            feature: Some(AudioFeature {
+                key: -1,
+                ..feature("a", 120.0, 0.5)
+            }),
+        };
+
+        assert_eq!(track.attribute(&TrackAttribute::Key(0)), None);
+        assert_eq!(track.attribute(&TrackAttribute::Tempo(0.0)), Some(120.0));
+    }
+}
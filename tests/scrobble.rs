@@ -0,0 +1,130 @@
+extern crate spotify_api;
+
+#[cfg(test)]
+mod scrobble {
+    use std::time::Duration;
+
+    use chrono::Utc;
+    use spotify_api::{player::CurrentlyPlayingObject, scrobble::*};
+
+    fn playing(id: &str, progress_ms: u32, duration_ms: u32) -> CurrentlyPlayingObject {
+        serde_json::from_value(serde_json::json!({
+            "context": null,
+            "timestamp": 0,
+            "progress_ms": progress_ms,
+            "is_playing": true,
+            "item": {
+                "artists": [],
+                "disc_number": 1,
+                "duration_ms": duration_ms,
+                "explicit": false,
+                "href": format!("https://api.spotify.com/v1/tracks/{}", id),
+                "id": id,
+                "name": id,
+                "track_number": 1,
+                "type": "track",
+                "uri": format!("spotify:track:{}", id),
+                "is_local": false
+            },
+            "currently_playing_type": "track"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn scrobble_after_threshold() {
+        let mut tracker = ScrobbleTracker::default();
+        let step = Duration::from_secs(30);
+
+        assert!(tracker
+            .observe(Some(&playing("a", 0, 200_000)), None, step, Utc::now())
+            .is_none());
+        for progress in &[30_000, 60_000, 90_000] {
+            assert!(tracker
+                .observe(
+                    Some(&playing("a", *progress, 200_000)),
+                    None,
+                    step,
+                    Utc::now()
+                )
+                .is_none());
+        }
+
+        let scrobble = tracker
+            .observe(
+                Some(&playing("a", 120_000, 200_000)),
+                None,
+                step,
+                Utc::now(),
+            )
+            .unwrap();
+        assert_eq!("spotify:track:a", scrobble.item.uri());
+        assert_eq!(120_000, scrobble.played_ms);
+
+        assert!(tracker
+            .observe(
+                Some(&playing("a", 150_000, 200_000)),
+                None,
+                step,
+                Utc::now()
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn skipped_tracks_are_not_scrobbled() {
+        let mut tracker = ScrobbleTracker::default();
+        let step = Duration::from_secs(30);
+
+        tracker.observe(Some(&playing("a", 0, 200_000)), None, step, Utc::now());
+        tracker.observe(Some(&playing("a", 30_000, 200_000)), None, step, Utc::now());
+        assert!(tracker
+            .observe(Some(&playing("b", 0, 200_000)), None, step, Utc::now())
+            .is_none());
+        assert_eq!(Some("spotify:track:b"), tracker.current_uri());
+
+        tracker.observe(None, None, step, Utc::now());
+        assert_eq!(None, tracker.current_uri());
+    }
+
+    #[test]
+    fn threshold() {
+        let threshold = ScrobbleThreshold::default();
+
+        assert_eq!(None, threshold.required(20_000));
+        assert_eq!(Some(Duration::from_secs(100)), threshold.required(200_000));
+        assert_eq!(Some(Duration::from_secs(240)), threshold.required(600_000));
+    }
+
+    #[test]
+    fn sink() {
+        let mut tracker = ScrobbleTracker::new(ScrobbleThreshold {
+            fraction: 0.1,
+            ..Default::default()
+        });
+        let mut sink: Vec<Scrobble> = Vec::new();
+        let step = Duration::from_secs(30);
+
+        tracker.observe(Some(&playing("a", 0, 200_000)), None, step, Utc::now());
+        let scrobble = tracker
+            .observe(Some(&playing("a", 30_000, 200_000)), None, step, Utc::now())
+            .unwrap();
+        sink.record(&scrobble).unwrap();
+
+        assert_eq!(1, sink.len());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn poll() {
+        dotenv::dotenv().ok();
+
+        let access_token = std::env::var("ACCESS_TOKEN").unwrap();
+        let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
+
+        let client = spotify_api::player::PlayerClient::new(&access_token, &refresh_token);
+        let mut scrobbler = Scrobbler::new(client, ScrobbleRequest::default());
+
+        dbg!(scrobbler.poll().await.unwrap());
+    }
+}
@@ -2,7 +2,7 @@ extern crate spotify_api;
 
 #[cfg(test)]
 mod track {
-    use spotify_api::track::*;
+    use spotify_api::{id::TrackId, track::*};
 
     #[tokio::test]
     async fn get_audio_analysis() {
@@ -11,10 +11,10 @@ mod track {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = TrackClient::new(&access_token, &refresh_token);
+        let client = TrackClient::new(&access_token, &refresh_token);
 
         let _ = client
-            .get_audio_analysis("3JIxjvbbDrA9ztYlNcp3yL")
+            .get_audio_analysis(&"3JIxjvbbDrA9ztYlNcp3yL".parse().unwrap())
             .await
             .unwrap();
     }
@@ -26,10 +26,10 @@ mod track {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = TrackClient::new(&access_token, &refresh_token);
+        let client = TrackClient::new(&access_token, &refresh_token);
 
         let _ = client
-            .get_audio_feature("3JIxjvbbDrA9ztYlNcp3yL")
+            .get_audio_feature(&"3JIxjvbbDrA9ztYlNcp3yL".parse().unwrap())
             .await
             .unwrap();
     }
@@ -41,12 +41,12 @@ mod track {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = TrackClient::new(&access_token, &refresh_token);
+        let client = TrackClient::new(&access_token, &refresh_token);
 
         let ids = vec![
-            "4JpKVNYnVcJ8tuMKjAj50A".to_string(),
-            "2NRANZE9UCmPAS5XVbXL40".to_string(),
-            "24JygzOLM0EmRQeGtFcIcG".to_string(),
+            "4JpKVNYnVcJ8tuMKjAj50A".parse().unwrap(),
+            "2NRANZE9UCmPAS5XVbXL40".parse().unwrap(),
+            "24JygzOLM0EmRQeGtFcIcG".parse().unwrap(),
         ];
 
         let _ = client.get_audio_features(ids).await.unwrap();
@@ -59,10 +59,10 @@ mod track {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = TrackClient::new(&access_token, &refresh_token);
+        let client = TrackClient::new(&access_token, &refresh_token);
 
         let track = client
-            .get_track("11dFghVXANMlKmJXsNCbNl", None)
+            .get_track(&"11dFghVXANMlKmJXsNCbNl".parse().unwrap(), None)
             .await
             .unwrap();
         assert_eq!("Cut To The Feeling", &track.name);
@@ -75,20 +75,218 @@ mod track {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = TrackClient::new(&access_token, &refresh_token);
+        let client = TrackClient::new(&access_token, &refresh_token);
 
-        let ids: Vec<String> = vec![
+        let ids: Vec<TrackId> = [
             "11dFghVXANMlKmJXsNCbNl",
             "20I6sIOMTCkB6w7ryavxtO",
             "7xGfFoTpQ2E7fRF5lN10tr",
         ]
         .repeat(101)
         .into_iter()
-        .map(|v| v.to_string())
+        .map(|v| v.parse().unwrap())
         .collect();
 
         let tracks = client.get_tracks(ids.clone(), None).await.unwrap();
 
         assert_eq!(ids.len(), tracks.len());
     }
+
+    fn interval(start: f64, duration: f64) -> TimeInterval {
+        TimeInterval {
+            start,
+            duration,
+            confidence: 1.0,
+        }
+    }
+
+    fn segment(start: f64, duration: f64, loudness_max: f64, pitch: usize) -> Segment {
+        let mut pitches = vec![0.0; 12];
+        pitches[pitch] = 1.0;
+
+        Segment {
+            start,
+            duration,
+            loudness_max,
+            pitches,
+            ..Default::default()
+        }
+    }
+
+    fn analysis(pitch: usize) -> AudioAnalysis {
+        AudioAnalysis {
+            bars: vec![interval(0.0, 2.0), interval(2.0, 2.0)],
+            beats: (0..8).map(|i| interval(i as f64 * 0.5, 0.5)).collect(),
+            sections: vec![
+                Section {
+                    start: 0.0,
+                    duration: 2.0,
+                    key: 0,
+                    mode: 1,
+                    ..Default::default()
+                },
+                Section {
+                    start: 2.0,
+                    duration: 2.0,
+                    key: 9,
+                    mode: 0,
+                    ..Default::default()
+                },
+            ],
+            segments: vec![
+                segment(0.0, 1.0, -10.0, pitch),
+                segment(1.0, 1.0, -20.0, pitch),
+                segment(2.0, 2.0, -5.0, (pitch + 7) % 12),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn audio_analysis_lookup() {
+        let analysis = analysis(0);
+
+        assert_eq!(analysis.duration(), 4.0);
+        assert_eq!(analysis.beat_at(1250).unwrap().0, 2);
+        assert_eq!(analysis.bar_at(2000).unwrap().0, 1);
+        assert!(analysis.bar_at(4000).is_none());
+        assert!(analysis.tatum_at(0).is_none());
+
+        let (index, section) = analysis.section_at(3000).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(section.key_name().unwrap(), "A minor");
+        assert_eq!(analysis.sections[0].key_name().unwrap(), "C major");
+        assert_eq!(analysis.section_boundaries(), vec![2.0]);
+    }
+
+    #[test]
+    fn audio_analysis_curves() {
+        let analysis = analysis(0);
+
+        assert_eq!(analysis.loudness_envelope(2000), vec![-15.0, -5.0]);
+        assert_eq!(analysis.tempo_curve(2000), vec![120.0, 120.0]);
+    }
+
+    #[test]
+    fn audio_analysis_chroma() {
+        let a = analysis(0);
+        let b = analysis(2);
+
+        let chroma = a.chroma(2);
+        assert_eq!(chroma.len(), 2);
+        assert_eq!(chroma[0][0], 1.0);
+        assert_eq!(chroma[1][7], 1.0);
+
+        assert!((a.chroma_similarity(&a, 4) - 1.0).abs() < 1e-9);
+        assert_eq!(a.chroma_similarity(&b, 4), 0.0);
+    }
+
+    #[test]
+    fn audio_analysis_deserialize() {
+        let analysis: AudioAnalysis = serde_json::from_str(
+            r#"{
+                "meta": {
+                    "analyzer_version": "4.0.0",
+                    "platform": "Linux",
+                    "detailed_status": "OK",
+                    "status_code": 0,
+                    "timestamp": 1495193577,
+                    "analysis_time": 6.93906,
+                    "input_process": "libvorbisfile L+R 44100->22050"
+                },
+                "track": {
+                    "num_samples": 4585515,
+                    "duration": 207.95985,
+                    "sample_md5": "",
+                    "offset_seconds": 0,
+                    "window_seconds": 0,
+                    "analysis_sample_rate": 22050,
+                    "analysis_channels": 1,
+                    "end_of_fade_in": 0.0,
+                    "start_of_fade_out": 201.13705,
+                    "loudness": -5.883,
+                    "tempo": 118.211,
+                    "tempo_confidence": 0.73,
+                    "time_signature": 4,
+                    "time_signature_confidence": 0.994,
+                    "key": 9,
+                    "key_confidence": 0.408,
+                    "mode": 0,
+                    "mode_confidence": 0.485,
+                    "codestring": "eJxVnAmS5DgOBL-",
+                    "code_version": 3.15,
+                    "echoprintstring": "eJzdnQ2OHLmOhK9=",
+                    "echoprint_version": 4.12,
+                    "synchstring": "eJx1mIlx7DAIQ1tKCR",
+                    "synch_version": 1.0,
+                    "rhythmstring": "eJyNXAmOLrmNvsoc4",
+                    "rhythm_version": 1.0
+                },
+                "bars": [{ "start": 0.49567, "duration": 2.18749, "confidence": 0.925 }],
+                "beats": [{ "start": 0.49567, "duration": 0.5, "confidence": 0.5 }],
+                "sections": [{
+                    "start": 0.0,
+                    "duration": 6.97092,
+                    "confidence": 1.0,
+                    "loudness": -14.938,
+                    "tempo": 113.178,
+                    "tempo_confidence": 0.647,
+                    "key": -1,
+                    "key_confidence": 0.0,
+                    "mode": -1,
+                    "mode_confidence": 0.0,
+                    "time_signature": 4,
+                    "time_signature_confidence": 1.0
+                }],
+                "segments": [{
+                    "start": 0.70154,
+                    "duration": 0.19891,
+                    "confidence": 0.435,
+                    "loudness_start": -23.053,
+                    "loudness_max": -14.25,
+                    "loudness_max_time": 0.07305,
+                    "pitches": [0.212, 0.141, 0.294, 0.8, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 1.0],
+                    "timbre": [42.115, 64.373, -0.233, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9]
+                }],
+                "tatums": [{ "start": 0.49567, "duration": 0.25, "confidence": 0.5 }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(analysis.meta.status_code, Some(0));
+        assert_eq!(analysis.track.key_name().unwrap(), "A minor");
+        assert_eq!(analysis.duration(), 207.95985);
+        assert!(analysis.sections[0].key_name().is_none());
+        assert!(analysis.segments[0].loudness_end.is_none());
+    }
+
+    #[test]
+    fn audio_features_allow_missing_entries() {
+        let features: Vec<Option<AudioFeature>> = serde_json::from_str(
+            r#"[null, {
+                "duration_ms": 180000,
+                "key": -1,
+                "mode": 1,
+                "time_signature": 4,
+                "acousticness": 0.1,
+                "danceability": 0.5,
+                "energy": 0.7,
+                "instrumentalness": 0.0,
+                "liveness": 0.1,
+                "loudness": -6.0,
+                "speechiness": 0.05,
+                "valence": 0.4,
+                "tempo": 120.0,
+                "id": "3JIxjvbbDrA9ztYlNcp3yL",
+                "uri": "spotify:track:3JIxjvbbDrA9ztYlNcp3yL",
+                "track_href": "https://api.spotify.com/v1/tracks/3JIxjvbbDrA9ztYlNcp3yL",
+                "analysis_url": "https://api.spotify.com/v1/audio-analysis/3JIxjvbbDrA9ztYlNcp3yL",
+                "type": "audio_features"
+            }]"#,
+        )
+        .unwrap();
+
+        assert!(features[0].is_none());
+        assert_eq!(-1, features[1].as_ref().unwrap().key);
+    }
 }
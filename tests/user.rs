@@ -11,7 +11,7 @@ mod user {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = UserClient::new(&access_token, &refresh_token);
+        let client = UserClient::new(&access_token, &refresh_token);
         client.get_current_user().await.unwrap();
     }
 
@@ -22,8 +22,11 @@ mod user {
         let access_token = std::env::var("ACCESS_TOKEN").unwrap();
         let refresh_token = std::env::var("REFRESH_TOKEN").unwrap();
 
-        let mut client = UserClient::new(&access_token, &refresh_token);
-        let user = client.get_user("tuggareutangranser").await.unwrap();
+        let client = UserClient::new(&access_token, &refresh_token);
+        let user = client
+            .get_user(&"tuggareutangranser".parse().unwrap())
+            .await
+            .unwrap();
 
         assert_eq!("Lilla Namo", &user.display_name.unwrap());
     }